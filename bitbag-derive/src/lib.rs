@@ -1,11 +1,11 @@
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
     parse::{Nothing, Parse, ParseStream},
     parse_macro_input, DataEnum, DeriveInput, Fields, Ident, LitStr,
 };
 
-#[proc_macro_derive(BitBaggable)]
+#[proc_macro_derive(BitBaggable, attributes(bitbag))]
 pub fn derive_bitbaggable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let user_struct = parse_macro_input!(input as DeriveInput);
     expand_bitbaggable(&user_struct)
@@ -21,6 +21,14 @@ pub fn derive_bitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .into()
 }
 
+#[proc_macro_derive(WideBitBaggable)]
+pub fn derive_wide_bitbaggable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let user_struct = parse_macro_input!(input as DeriveInput);
+    expand_wide_bitbaggable(&user_struct)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 #[proc_macro_attribute]
 pub fn check(
     attr: proc_macro::TokenStream,
@@ -95,10 +103,79 @@ fn get_repr_ident(input: &DeriveInput) -> syn::Result<ReprIntIdent> {
     }
 }
 
+/// Options from `#[bitbag(..)]` attributes on the enum
+#[derive(Default)]
+struct Options {
+    order: Option<Ident>,
+    encoding: Option<Ident>,
+    bnum: Option<Ident>,
+    ffi: Option<LitStr>,
+}
+
+fn get_options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("bitbag") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("order") {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                let variant = match lit.value().as_str() {
+                    "declaration" => "Declaration",
+                    "ascending" => "Ascending",
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "bitbag: order must be one of [\"declaration\", \"ascending\"]",
+                        ))
+                    }
+                };
+                options.order = Some(Ident::new(variant, lit.span()));
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                let variant = match lit.value().as_str() {
+                    "auto" => "Auto",
+                    "names" => "Names",
+                    "bits" => "Bits",
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "bitbag: serde must be one of [\"auto\", \"names\", \"bits\"]",
+                        ))
+                    }
+                };
+                options.encoding = Some(Ident::new(variant, lit.span()));
+                Ok(())
+            } else if meta.path.is_ident("bnum") {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                options.bnum = Some(lit.parse::<Ident>()?);
+                Ok(())
+            } else if meta.path.is_ident("ffi") {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                if lit.parse::<Ident>().is_err() {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "bitbag: ffi must be a prefix for function names, like \"my_flags\"",
+                    ));
+                }
+                options.ffi = Some(lit);
+                Ok(())
+            } else {
+                Err(meta.error("bitbag: unrecognised option"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
 fn extract_enum_and_repr(input: &DeriveInput) -> syn::Result<(&DataEnum, ReprIntIdent)> {
     let syn::Data::Enum(data) = &input.data else {
-        return Err(
-        syn::Error::new_spanned(input, "bitbag: only enums are supported"));
+        return Err(syn::Error::new_spanned(
+            input,
+            "bitbag: only enums are supported",
+        ));
     };
     let repr = get_repr_ident(input)?;
 
@@ -124,26 +201,263 @@ fn extract_enum_and_repr(input: &DeriveInput) -> syn::Result<(&DataEnum, ReprInt
 
 fn expand_bitbaggable(input: &DeriveInput) -> syn::Result<TokenStream> {
     let (data, repr) = extract_enum_and_repr(input)?;
+    let options = get_options(input)?;
     let user_ident = &input.ident;
+    let order = options.order.map(|order| {
+        quote! {
+            const ORDER: bitbag::Order = bitbag::Order::#order;
+        }
+    });
+    let encoding = options.encoding.map(|encoding| {
+        quote! {
+            const ENCODING: bitbag::Encoding = bitbag::Encoding::#encoding;
+        }
+    });
+    // With `bnum`, each discriminant is the index of the variant's bit in the wide repr
+    let bnum = options.bnum;
+    let (repr_ty, into_repr) = match &bnum {
+        Some(wide) => (
+            quote!(bitbag::bnum::#wide),
+            quote!(bitbag::bnum::#wide::power_of_two(self as u32)),
+        ),
+        None => (quote!(#repr), quote!(self as #repr)),
+    };
     let names_and_values = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let name = syn::LitStr::new(&ident.to_string(), ident.span());
+        match &bnum {
+            Some(wide) => quote! {
+                (#name, Self::#ident, bitbag::bnum::#wide::power_of_two(Self::#ident as u32))
+            },
+            None => quote! {
+                (#name, Self::#ident, Self::#ident as _)
+            },
+        }
+    });
+    let name_arms = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let name = syn::LitStr::new(&ident.to_string(), ident.span());
+        quote! {
+            Self::#ident => #name
+        }
+    });
+
+    let defmt_names = data.variants.iter().map(|variant| {
         let ident = &variant.ident;
         let name = syn::LitStr::new(&ident.to_string(), ident.span());
         quote! {
-            (#name, Self::#ident, Self::#ident as _)
+            #ident => #name
+        }
+    });
+
+    let mut sorted_names = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(ix, variant)| (variant.ident.to_string(), ix))
+        .collect::<Vec<_>>();
+    sorted_names.sort();
+    let sorted_names = sorted_names.iter().map(|(name, ix)| quote!((#name, #ix)));
+
+    // Precompute how to walk set bits, see `BitBaggable::SINGLE_BITS`.
+    // Wide reprs don't have const bit operations, so fall back to searching the variants.
+    let bit_walk = bnum.is_none().then(|| {
+        quote! {
+            fn all_bits() -> Self::ReprT {
+                Self::__BITBAG_ALL_BITS
+            }
+            const SINGLE_BITS: bool = {
+                let mut single = true;
+                let mut ix = 0;
+                while ix < Self::VARIANTS.len() {
+                    let repr = Self::VARIANTS[ix].2;
+                    if repr.count_ones() != 1
+                        || (ix > 0 && repr.trailing_zeros() <= Self::VARIANTS[ix - 1].2.trailing_zeros())
+                    {
+                        single = false;
+                    }
+                    ix += 1;
+                }
+                single
+            };
+            fn variant_at_bit(bit: u32) -> Option<usize> {
+                const TABLE: [usize; <#repr>::BITS as usize] = {
+                    let variants = <#user_ident as bitbag::BitBaggable>::VARIANTS;
+                    let mut table = [usize::MAX; <#repr>::BITS as usize];
+                    let mut ix = 0;
+                    while ix < variants.len() {
+                        let repr = variants[ix].2;
+                        let bit = repr.trailing_zeros() as usize;
+                        if repr.count_ones() == 1 && table[bit] == usize::MAX {
+                            table[bit] = ix;
+                        }
+                        ix += 1;
+                    }
+                    table
+                };
+                match TABLE.get(bit as usize) {
+                    Some(&ix) if ix != usize::MAX => Some(ix),
+                    _ => None,
+                }
+            }
+        }
+    });
+
+    let ffi = options
+        .ffi
+        .map(|prefix| match &bnum {
+            Some(_) => Err(syn::Error::new_spanned(
+                &prefix,
+                "bitbag: ffi can't be used with bnum, which has no C equivalent",
+            )),
+            None => Ok(expand_ffi(&prefix, data, user_ident, &repr)),
+        })
+        .transpose()?;
+
+    let all_bits = bnum.is_none().then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #user_ident {
+                const __BITBAG_ALL_BITS: #repr = {
+                    let variants = <#user_ident as bitbag::BitBaggable>::VARIANTS;
+                    let mut all = 0;
+                    let mut ix = 0;
+                    while ix < variants.len() {
+                        all |= variants[ix].2;
+                        ix += 1;
+                    }
+                    all
+                };
+
+                #[doc(hidden)]
+                pub const fn __bitbag_is_valid(repr: #repr) -> bool {
+                    repr & !Self::__BITBAG_ALL_BITS == 0
+                }
+            }
         }
     });
 
     Ok(quote! {
         #[automatically_derived]
         impl bitbag::BitBaggable for #user_ident {
-            type ReprT = #repr;
+            type ReprT = #repr_ty;
             fn into_repr(self) -> Self::ReprT {
-                self as #repr
+                #into_repr
             }
             const VARIANTS: &'static [(&'static str, Self, Self::ReprT)] = &[
                     #(#names_and_values,)*
                 ];
+            #order
+            #encoding
+            fn name(self) -> &'static str {
+                match self {
+                    #(#name_arms,)*
+                }
+            }
+            fn variant_index(name: &str) -> Option<usize> {
+                const SORTED: &[(&str, usize)] = &[#(#sorted_names,)*];
+                SORTED
+                    .binary_search_by(|(candidate, _)| (*candidate).cmp(name))
+                    .ok()
+                    .map(|ix| SORTED[ix].1)
+            }
+            bitbag::__defmt_name! { #(#defmt_names,)* }
+            #bit_walk
+        }
+
+        #all_bits
+        #ffi
+
+        const _: () = {
+            assert!(core::mem::size_of::<bitbag::BitBag<#user_ident>>() == core::mem::size_of::<#repr_ty>());
+            assert!(core::mem::align_of::<bitbag::BitBag<#user_ident>>() == core::mem::align_of::<#repr_ty>());
+        };
+    })
+}
+
+/// `extern "C"` helpers for `#[bitbag(ffi = "prefix")]`, named `prefix_contains` and so on
+fn expand_ffi(
+    prefix: &LitStr,
+    data: &DataEnum,
+    user_ident: &Ident,
+    repr: &ReprIntIdent,
+) -> TokenStream {
+    let prefix = prefix.value();
+    let contains = format_ident!("{}_contains", prefix);
+    let is_valid = format_ident!("{}_is_valid", prefix);
+    let all = format_ident!("{}_all", prefix);
+    let name = format_ident!("{}_name", prefix);
+    let names = data.variants.iter().map(|variant| {
+        let name = format!("{}\0", variant.ident);
+        syn::LitStr::new(&name, variant.ident.span())
+    });
+    let doc = |text: String| quote!(#[doc = #text]);
+    let contains_doc = doc(format!(
+        "Whether every bit of `flags` is set in `bits`, for [`{user_ident}`] flags"
+    ));
+    let is_valid_doc = doc(format!(
+        "Whether every bit set in `bits` is part of a [`{user_ident}`] flag"
+    ));
+    let all_doc = doc(format!(
+        "Every bit which is part of a [`{user_ident}`] flag"
+    ));
+    let name_doc = doc(format!(
+        "The name of the [`{user_ident}`] flag which is exactly `flag`, as a static NUL-terminated string, or null if there isn't one"
+    ));
+    quote! {
+        #contains_doc
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #contains(bits: #repr, flags: #repr) -> bool {
+            bits & flags == flags
+        }
+
+        #is_valid_doc
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #is_valid(bits: #repr) -> bool {
+            #user_ident::__bitbag_is_valid(bits)
+        }
+
+        #all_doc
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #all() -> #repr {
+            #user_ident::__BITBAG_ALL_BITS
+        }
+
+        #name_doc
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #name(flag: #repr) -> *const core::ffi::c_char {
+            const NAMES: &[&str] = &[#(#names,)*];
+            match <#user_ident as bitbag::BitBaggable>::VARIANTS
+                .iter()
+                .position(|(_, _, repr)| *repr == flag)
+            {
+                Some(ix) => NAMES[ix].as_ptr().cast(),
+                None => core::ptr::null(),
+            }
+        }
+    }
+}
 
+fn expand_wide_bitbaggable(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let (data, _) = extract_enum_and_repr(input)?;
+    let user_ident = &input.ident;
+    let names_and_bits = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let name = syn::LitStr::new(&ident.to_string(), ident.span());
+        quote! {
+            (#name, Self::#ident, Self::#ident as usize)
+        }
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl bitbag::WideBitBaggable for #user_ident {
+            fn bit(self) -> usize {
+                self as usize
+            }
+            const VARIANTS: &'static [(&'static str, Self, usize)] = &[
+                    #(#names_and_bits,)*
+                ];
         }
     })
 }
@@ -0,0 +1,8 @@
+#[derive(bitbag_derive::BitBaggable)]
+#[bitbag(ffi = "my-flags")]
+#[repr(u8)]
+enum Flags {
+    A = 0b0001,
+}
+
+fn main() {}
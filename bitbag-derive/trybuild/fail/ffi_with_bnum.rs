@@ -0,0 +1,8 @@
+#[derive(bitbag_derive::BitBaggable)]
+#[bitbag(bnum = "U256", ffi = "wide_flags")]
+#[repr(u8)]
+enum WideFlags {
+    A = 0,
+}
+
+fn main() {}
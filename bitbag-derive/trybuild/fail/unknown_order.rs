@@ -0,0 +1,8 @@
+#[derive(bitbag_derive::BitBaggable)]
+#[bitbag(order = "numeric")]
+#[repr(u8)]
+enum BadFlags {
+    A = 0b0001,
+}
+
+fn main() {}
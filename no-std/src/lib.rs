@@ -0,0 +1,42 @@
+//! Uses `bitbag` without its default `std` feature, so the core of the crate stays `no_std`.
+//!
+//! Check it with `cargo build -p bitbag-no-std`, which resolves features for this crate alone.
+//! Building the whole workspace unifies them with the main crate's defaults, which enable `std`.
+
+#![cfg_attr(not(test), no_std)]
+
+use bitbag::{BitBag, BitBaggable, BitOr};
+use core::fmt::{self, Write};
+
+#[derive(BitBaggable, BitOr, Debug, Clone, Copy)]
+#[repr(u8)]
+pub enum Flags {
+    A = 0b0001,
+    B = 0b0010,
+    C = 0b0100,
+}
+
+/// Construct, combine, iterate and display a bag, without allocating
+pub fn exercise(out: &mut impl Write) -> fmt::Result {
+    let mut bag = Flags::A | Flags::B;
+    bag.set(Flags::C);
+    bag &= !BitBag::new(0b0010);
+    for flag in bag {
+        write!(out, "{:?}, ", flag)?;
+    }
+    write!(out, "{} of {}", bag, BitBag::<Flags>::all())?;
+    if BitBag::<Flags>::new_strict(0b1000).is_err() {
+        out.write_str(", strict")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn exercise() {
+        let mut out = String::new();
+        super::exercise(&mut out).unwrap();
+        assert_eq!(out, "A, C, A | C of A | B | C, strict");
+    }
+}
@@ -0,0 +1,107 @@
+//! [arbitrary](https://docs.rs/arbitrary) support, enabled by the `arbitrary` feature.
+//!
+//! A [`BitBag`] is built from one byte of input per declared flag, so it only ever contains recognised bits.
+//! Fuzz targets which should also see unrecognised bits can take an [`AnyBits`] instead.
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use bitbag::{arbitrary::AnyBits, BitBag, BitBaggable};
+//!
+//! #[derive(BitBaggable)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! let mut u = Unstructured::new(&[1, 0, 0xff]);
+//! let bag = BitBag::<Flags>::arbitrary(&mut u).unwrap();
+//! assert_eq!(bag.get(), 0b0001);
+//! let AnyBits(raw) = AnyBits::<Flags>::arbitrary(&mut u).unwrap();
+//! assert_eq!(raw.get(), 0xff);
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+use core::fmt::{self, Debug};
+
+impl<'a, PossibleFlagsT: BitBaggable> Arbitrary<'a> for BitBag<PossibleFlagsT> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut result = Ok(());
+        let bag = BitBag::from_bools(|| match bool::arbitrary(u) {
+            Ok(set) => set,
+            Err(e) => {
+                result = Err(e);
+                false
+            }
+        });
+        result.map(|()| bag)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let (lower, upper) = bool::size_hint(depth);
+        let len = PossibleFlagsT::VARIANTS.len();
+        (lower * len, upper.map(|upper| upper * len))
+    }
+}
+
+/// A bag built from an arbitrary primitive, permitting (and preserving) unrecognised bits, as [`BitBag::new`] does.
+pub struct AnyBits<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+impl<'a, PossibleFlagsT: BitBaggable> Arbitrary<'a> for AnyBits<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        PossibleFlagsT::ReprT::arbitrary(u).map(|repr| Self(BitBag::new(repr)))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        PossibleFlagsT::ReprT::size_hint(depth)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for AnyBits<PossibleFlagsT>
+where
+    BitBag<PossibleFlagsT>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyBits").field(&self.0).finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for AnyBits<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for AnyBits<PossibleFlagsT> {}
+
+#[cfg(test)]
+mod tests {
+    use super::AnyBits;
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn recognised() {
+        let mut u = Unstructured::new(&[1, 0, 1, 0]);
+        assert_eq!(BitBag::<FooFlags>::arbitrary(&mut u).unwrap(), A | C);
+        let bytes = [0xff; 64];
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            let bag = BitBag::<FooFlags>::arbitrary(&mut u).unwrap();
+            assert!(!bag.has_unrecognised_bits());
+        }
+        assert_eq!(BitBag::<FooFlags>::size_hint(0), (4, Some(4)));
+    }
+
+    #[test]
+    fn any_bits() {
+        let mut u = Unstructured::new(&[0xf0]);
+        let AnyBits(bag) = AnyBits::<FooFlags>::arbitrary(&mut u).unwrap();
+        assert_eq!(bag.get(), 0xf0);
+        assert!(bag.has_unrecognised_bits());
+    }
+}
@@ -0,0 +1,115 @@
+//! [async-graphql](https://docs.rs/async-graphql) support, enabled by the `async-graphql` feature.
+//!
+//! A [`BitBag`] is a scalar named after the full path of the enum, like `BitBag_my_crate_Flags`, holding a list of flag names.
+//! Input fails on the first name which isn't defined in the enum, naming it.
+//! Output lists the set flags in [`BitBaggable::ORDER`], leaving out any unrecognised bits.
+
+use crate::{parse::by_name, schema_name, BitBag, BitBaggable, ParseError};
+use ::async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, TypeName, Value};
+use std::{borrow::Cow, fmt::Binary};
+
+impl<PossibleFlagsT: BitBaggable> TypeName for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Send + Sync,
+{
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(schema_name::<PossibleFlagsT>())
+    }
+}
+
+#[Scalar(name_type)]
+impl<PossibleFlagsT: BitBaggable> ScalarType for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary + Send + Sync,
+{
+    fn parse(value: Value) -> InputValueResult<Self> {
+        let Value::List(names) = value else {
+            return Err(InputValueError::expected_type(value));
+        };
+        let mut bag = BitBag::empty();
+        for name in names {
+            let name = match name {
+                Value::String(name) => name,
+                Value::Enum(name) => name.to_string(),
+                other => return Err(InputValueError::expected_type(other)),
+            };
+            match by_name::<PossibleFlagsT>(&name) {
+                Some(repr) => bag.set_raw(repr),
+                None => {
+                    return Err(InputValueError::custom(
+                        ParseError::<PossibleFlagsT>::UnknownName(name),
+                    ))
+                }
+            };
+        }
+        Ok(bag)
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::List(_))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::List(
+            self.iter_names()
+                .map(|name| Value::String(name.to_string()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::async_graphql::{InputType, ScalarType, Value};
+
+    #[test]
+    fn roundtrip() {
+        let bag = A | C;
+        let value = ScalarType::to_value(&bag);
+        assert_eq!(
+            value,
+            Value::List(vec![Value::String("A".into()), Value::String("C".into())])
+        );
+        assert_eq!(<BitBag<FooFlags> as ScalarType>::parse(value).unwrap(), bag);
+        assert_eq!(
+            <BitBag<FooFlags> as InputType>::type_name(),
+            "BitBag_bitbag_tests_FooFlags"
+        );
+    }
+
+    #[test]
+    fn order() {
+        use crate::{self as bitbag, BitBaggable};
+
+        #[derive(BitBaggable)]
+        #[bitbag(order = "ascending")]
+        #[repr(u8)]
+        enum Ascending {
+            High = 0b10,
+            Low = 0b01,
+        }
+        assert_eq!(
+            ScalarType::to_value(&BitBag::<Ascending>::all()),
+            Value::List(vec![
+                Value::String("Low".into()),
+                Value::String("High".into())
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_name() {
+        let e = <BitBag<FooFlags> as ScalarType>::parse(Value::List(vec![
+            Value::String("A".into()),
+            Value::String("Z".into()),
+        ]))
+        .unwrap_err();
+        assert!(e
+            .into_server_error(Default::default())
+            .message
+            .contains("\"Z\""));
+        assert!(<BitBag<FooFlags> as ScalarType>::parse(Value::Number(1.into())).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! A bag which can be shared between threads, see [`AtomicBitBag`].
+//!
+//! With the `portable-atomic` feature, the atomics come from [portable-atomic](https://docs.rs/portable-atomic) instead of `std`,
+//! so they're also available on targets without native atomics or compare-and-swap (such as `thumbv6m`),
+//! falling back to however portable-atomic is configured, such as its `critical-section` feature.
+
+use crate::{BitBag, BitBaggable};
+#[cfg(feature = "portable-atomic")]
+use ::portable_atomic as atomic;
+pub use atomic::Ordering;
+#[cfg(not(feature = "portable-atomic"))]
+use core::sync::atomic;
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+use num::PrimInt;
+
+/// A primitive with a matching atomic type, so it can be the repr of an [`AtomicBitBag`].
+///
+/// This is implemented for every primitive integer the target has atomics for, except `u128` and `i128`.
+/// With the `portable-atomic` feature, it's implemented for every primitive integer.
+pub trait AtomicRepr: PrimInt {
+    /// The atomic type, such as [`AtomicU8`](atomic::AtomicU8) for `u8`
+    type Atomic;
+    fn new_atomic(repr: Self) -> Self::Atomic;
+    fn into_inner(atomic: Self::Atomic) -> Self;
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+    fn store(atomic: &Self::Atomic, repr: Self, order: Ordering);
+    fn swap(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self;
+    fn fetch_or(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self;
+    fn fetch_and(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self;
+    fn fetch_xor(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self;
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+    fn compare_exchange_weak(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+}
+
+macro_rules! impl_atomic_repr {
+    ($(#[$cfg:meta] $($repr:ty => $atomic:ident),*;)*) => {$($(
+        #[$cfg]
+        impl AtomicRepr for $repr {
+            type Atomic = atomic::$atomic;
+
+            fn new_atomic(repr: Self) -> Self::Atomic {
+                atomic::$atomic::new(repr)
+            }
+
+            fn into_inner(atomic: Self::Atomic) -> Self {
+                atomic.into_inner()
+            }
+
+            fn load(atomic: &Self::Atomic, order: Ordering) -> Self {
+                atomic.load(order)
+            }
+
+            fn store(atomic: &Self::Atomic, repr: Self, order: Ordering) {
+                atomic.store(repr, order)
+            }
+
+            fn swap(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self {
+                atomic.swap(repr, order)
+            }
+
+            fn fetch_or(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self {
+                atomic.fetch_or(repr, order)
+            }
+
+            fn fetch_and(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self {
+                atomic.fetch_and(repr, order)
+            }
+
+            fn fetch_xor(atomic: &Self::Atomic, repr: Self, order: Ordering) -> Self {
+                atomic.fetch_xor(repr, order)
+            }
+
+            fn compare_exchange(
+                atomic: &Self::Atomic,
+                current: Self,
+                new: Self,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<Self, Self> {
+                atomic.compare_exchange(current, new, success, failure)
+            }
+
+            fn compare_exchange_weak(
+                atomic: &Self::Atomic,
+                current: Self,
+                new: Self,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<Self, Self> {
+                atomic.compare_exchange_weak(current, new, success, failure)
+            }
+        }
+    )*)*};
+}
+
+impl_atomic_repr! {
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+    u8 => AtomicU8, i8 => AtomicI8;
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "16"))]
+    u16 => AtomicU16, i16 => AtomicI16;
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+    u32 => AtomicU32, i32 => AtomicI32;
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+    u64 => AtomicU64, i64 => AtomicI64;
+    #[cfg(feature = "portable-atomic")]
+    u128 => AtomicU128, i128 => AtomicI128;
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+    usize => AtomicUsize, isize => AtomicIsize;
+}
+
+/// A [`BitBag`] which can be modified through a shared reference, backed by the matching atomic integer.
+///
+/// Reads use [`Ordering::Acquire`], writes use [`Ordering::Release`],
+/// and read-modify-write operations use [`Ordering::AcqRel`].
+/// Each method has a `_with` variant taking the ordering instead, such as [`AtomicBitBag::fetch_or_with`].
+pub struct AtomicBitBag<PossibleFlagsT: BitBaggable>
+where
+    PossibleFlagsT::ReprT: AtomicRepr,
+{
+    inner: <PossibleFlagsT::ReprT as AtomicRepr>::Atomic,
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> AtomicBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: AtomicRepr,
+{
+    pub fn new(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self {
+            inner: PossibleFlagsT::ReprT::new_atomic(bag.repr),
+            _flags: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::into_inner(self.inner))
+    }
+
+    pub fn load(&self) -> BitBag<PossibleFlagsT> {
+        self.load_with(Ordering::Acquire)
+    }
+
+    /// [`AtomicBitBag::load`] with the given ordering
+    pub fn load_with(&self, order: Ordering) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::load(&self.inner, order))
+    }
+
+    pub fn store(&self, bag: BitBag<PossibleFlagsT>) {
+        self.store_with(bag, Ordering::Release)
+    }
+
+    /// [`AtomicBitBag::store`] with the given ordering
+    pub fn store_with(&self, bag: BitBag<PossibleFlagsT>, order: Ordering) {
+        PossibleFlagsT::ReprT::store(&self.inner, bag.repr, order)
+    }
+
+    /// Replace the bag, returning the previous one
+    pub fn swap(&self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        self.swap_with(bag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::swap`] with the given ordering
+    pub fn swap_with(
+        &self,
+        bag: BitBag<PossibleFlagsT>,
+        order: Ordering,
+    ) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::swap(&self.inner, bag.repr, order))
+    }
+
+    /// Clear the bag, returning the bits which were set.
+    ///
+    /// Flags set by other threads are either returned here, or left in the bag for next time, but never lost.
+    pub fn swap_empty(&self) -> BitBag<PossibleFlagsT> {
+        self.swap(BitBag::empty())
+    }
+
+    /// [`AtomicBitBag::swap_empty`] with the given ordering
+    pub fn swap_empty_with(&self, order: Ordering) -> BitBag<PossibleFlagsT> {
+        self.swap_with(BitBag::empty(), order)
+    }
+
+    /// Set `flag`, returning whether any of its bits weren't already set, as [`BitBag::insert`]
+    pub fn insert(&self, flag: PossibleFlagsT) -> bool {
+        self.insert_with(flag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::insert`] with the given ordering
+    pub fn insert_with(&self, flag: PossibleFlagsT, order: Ordering) -> bool {
+        let repr = flag.into_repr();
+        !self
+            .fetch_or_with(BitBag::new(repr), order)
+            .is_set_raw(repr)
+    }
+
+    /// Unset `flag`, returning whether it was set
+    pub fn remove(&self, flag: PossibleFlagsT) -> bool {
+        self.remove_with(flag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::remove`] with the given ordering
+    pub fn remove_with(&self, flag: PossibleFlagsT, order: Ordering) -> bool {
+        let repr = flag.into_repr();
+        self.fetch_and_with(BitBag::new(!repr), order)
+            .is_set_raw(repr)
+    }
+
+    /// Flip `flag`, returning whether it was set
+    pub fn toggle(&self, flag: PossibleFlagsT) -> bool {
+        self.toggle_with(flag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::toggle`] with the given ordering
+    pub fn toggle_with(&self, flag: PossibleFlagsT, order: Ordering) -> bool {
+        let repr = flag.into_repr();
+        self.fetch_xor_with(BitBag::new(repr), order)
+            .is_set_raw(repr)
+    }
+
+    /// Set the bits in `bag`, returning the previous bag
+    pub fn fetch_or(&self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        self.fetch_or_with(bag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::fetch_or`] with the given ordering
+    pub fn fetch_or_with(
+        &self,
+        bag: BitBag<PossibleFlagsT>,
+        order: Ordering,
+    ) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::fetch_or(
+            &self.inner,
+            bag.repr,
+            order,
+        ))
+    }
+
+    /// Keep only the bits in `bag`, returning the previous bag
+    pub fn fetch_and(&self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        self.fetch_and_with(bag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::fetch_and`] with the given ordering
+    pub fn fetch_and_with(
+        &self,
+        bag: BitBag<PossibleFlagsT>,
+        order: Ordering,
+    ) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::fetch_and(
+            &self.inner,
+            bag.repr,
+            order,
+        ))
+    }
+
+    /// Flip the bits in `bag`, returning the previous bag
+    pub fn fetch_xor(&self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        self.fetch_xor_with(bag, Ordering::AcqRel)
+    }
+
+    /// [`AtomicBitBag::fetch_xor`] with the given ordering
+    pub fn fetch_xor_with(
+        &self,
+        bag: BitBag<PossibleFlagsT>,
+        order: Ordering,
+    ) -> BitBag<PossibleFlagsT> {
+        BitBag::new(PossibleFlagsT::ReprT::fetch_xor(
+            &self.inner,
+            bag.repr,
+            order,
+        ))
+    }
+
+    /// Store `new` if the bag is still `current`.
+    ///
+    /// Returns the previous bag, as `Ok` if it was `current` and so was replaced, or `Err` if not.
+    pub fn compare_exchange(
+        &self,
+        current: BitBag<PossibleFlagsT>,
+        new: BitBag<PossibleFlagsT>,
+    ) -> Result<BitBag<PossibleFlagsT>, BitBag<PossibleFlagsT>> {
+        self.compare_exchange_with(current, new, Ordering::AcqRel, Ordering::Acquire)
+    }
+
+    /// [`AtomicBitBag::compare_exchange`] with the given orderings, as for [`AtomicU8::compare_exchange`](atomic::AtomicU8::compare_exchange)
+    pub fn compare_exchange_with(
+        &self,
+        current: BitBag<PossibleFlagsT>,
+        new: BitBag<PossibleFlagsT>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<BitBag<PossibleFlagsT>, BitBag<PossibleFlagsT>> {
+        PossibleFlagsT::ReprT::compare_exchange(
+            &self.inner,
+            current.repr,
+            new.repr,
+            success,
+            failure,
+        )
+        .map(BitBag::new)
+        .map_err(BitBag::new)
+    }
+
+    /// Replace the bag with the result of `f`, retrying if another thread changes it in the meantime.
+    ///
+    /// `f` may be called several times, and can return `None` to leave the bag as it is.
+    /// Returns the previous bag, as `Ok` if `f` returned `Some`, or `Err` if not.
+    /// ```
+    /// # use bitbag::{AtomicBitBag, BitBag, BitBaggable};
+    /// #[derive(Debug, BitBaggable)]
+    /// #[repr(u8)]
+    /// enum State {
+    ///     Busy = 0b01,
+    ///     Error = 0b10,
+    /// }
+    ///
+    /// let state = AtomicBitBag::<State>::default();
+    /// // only set `Busy` if `Error` is clear
+    /// let set_busy = |mut bag: BitBag<State>| match bag.is_set(State::Error) {
+    ///     true => None,
+    ///     false => Some(*bag.set(State::Busy)),
+    /// };
+    /// assert!(state.fetch_update(set_busy).is_ok());
+    /// assert!(state.load().is_set(State::Busy));
+    ///
+    /// state.store(BitBag::just(State::Error));
+    /// assert!(state.fetch_update(set_busy).is_err());
+    /// assert!(!state.load().is_set(State::Busy));
+    /// ```
+    pub fn fetch_update(
+        &self,
+        f: impl FnMut(BitBag<PossibleFlagsT>) -> Option<BitBag<PossibleFlagsT>>,
+    ) -> Result<BitBag<PossibleFlagsT>, BitBag<PossibleFlagsT>> {
+        self.fetch_update_with(Ordering::AcqRel, Ordering::Acquire, f)
+    }
+
+    /// [`AtomicBitBag::fetch_update`] with the given orderings, as for [`AtomicU8::fetch_update`](atomic::AtomicU8::fetch_update)
+    pub fn fetch_update_with(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: impl FnMut(BitBag<PossibleFlagsT>) -> Option<BitBag<PossibleFlagsT>>,
+    ) -> Result<BitBag<PossibleFlagsT>, BitBag<PossibleFlagsT>> {
+        let mut previous = self.load_with(fetch_order);
+        while let Some(new) = f(previous) {
+            match PossibleFlagsT::ReprT::compare_exchange_weak(
+                &self.inner,
+                previous.repr,
+                new.repr,
+                set_order,
+                fetch_order,
+            ) {
+                Ok(repr) => return Ok(BitBag::new(repr)),
+                Err(repr) => previous = BitBag::new(repr),
+            }
+        }
+        Err(previous)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Default for AtomicBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: AtomicRepr,
+{
+    fn default() -> Self {
+        Self::new(BitBag::empty())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>> for AtomicBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: AtomicRepr,
+{
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self::new(bag)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for AtomicBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: AtomicRepr + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bag = BitBag::<PossibleFlagsT>::new(PossibleFlagsT::ReprT::load(
+            &self.inner,
+            Ordering::Relaxed,
+        ));
+        Debug::fmt(&bag, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtomicBitBag, Ordering};
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+
+    #[test]
+    fn flags() {
+        let bag = AtomicBitBag::new(BitBag::just(A));
+        assert!(bag.insert(B));
+        assert!(!bag.insert(B));
+        assert!(bag.remove(A));
+        assert!(!bag.remove(A));
+        assert!(!bag.toggle(C));
+        assert_eq!(bag.load(), B | C);
+        assert!(bag.toggle(C));
+        assert_eq!(bag.swap(A | D), BitBag::just(B));
+        assert_eq!(bag.into_inner(), A | D);
+    }
+
+    #[test]
+    fn fetch() {
+        let bag = AtomicBitBag::<FooFlags>::default();
+        assert!(bag.fetch_or(A | B).is_empty());
+        assert_eq!(bag.fetch_xor(B | C), A | B);
+        assert_eq!(bag.fetch_and(A | B), A | C);
+        assert_eq!(bag.load(), BitBag::just(A));
+    }
+
+    #[test]
+    fn compare_exchange() {
+        let bag = AtomicBitBag::new(A | B);
+        assert_eq!(bag.compare_exchange(A | C, D | C), Err(A | B));
+        assert_eq!(bag.compare_exchange(A | B, D | C), Ok(A | B));
+        assert_eq!(bag.load(), C | D);
+    }
+
+    #[test]
+    fn orderings() {
+        let bag = AtomicBitBag::<FooFlags>::default();
+        assert!(bag.insert_with(A, Ordering::Relaxed));
+        bag.store_with(bag.load_with(Ordering::Relaxed) | B, Ordering::SeqCst);
+        assert_eq!(
+            bag.fetch_update_with(Ordering::SeqCst, Ordering::Relaxed, |bag| Some(bag | C)),
+            Ok(A | B)
+        );
+        assert_eq!(bag.swap_with(A | D, Ordering::Relaxed), A | B | C);
+        assert_eq!(bag.swap_empty_with(Ordering::Relaxed), A | D);
+        assert!(bag.swap_empty().is_empty());
+    }
+
+    #[test]
+    fn threads() {
+        let bag = AtomicBitBag::<FooFlags>::default();
+        std::thread::scope(|scope| {
+            for flag in [A, B, C, D] {
+                let bag = &bag;
+                scope.spawn(move || bag.insert(flag));
+            }
+        });
+        assert_eq!(bag.load(), BitBag::all());
+    }
+
+    #[cfg(feature = "portable-atomic")]
+    #[test]
+    fn wide() {
+        use crate as bitbag;
+        use crate::BitBaggable;
+
+        #[derive(Debug, Clone, Copy, BitBaggable)]
+        #[repr(u128)]
+        enum Wide {
+            Low = 1,
+            High = 1 << 127,
+        }
+
+        let bag = AtomicBitBag::<Wide>::default();
+        assert!(bag.insert(Wide::High));
+        assert!(!bag.toggle(Wide::Low));
+        assert_eq!(bag.load().get(), 1 << 127 | 1);
+    }
+}
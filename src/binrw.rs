@@ -0,0 +1,105 @@
+//! [binrw](https://docs.rs/binrw) support, enabled by the `binrw` feature.
+//!
+//! A [`BitBag`] is read and written as its inner primitive, in the endianness of the surrounding struct.
+//! Reading permits (and preserves) unrecognised bits, as [`BitBag::new`] does;
+//! add `#[br(assert(!flags.has_unrecognised_bits()))]` to reject them.
+
+use crate::{BitBag, BitBaggable};
+use ::binrw::{
+    io::{Read, Seek, Write},
+    meta::{EndianKind, ReadEndian, WriteEndian},
+    BinRead, BinResult, BinWrite, Endian,
+};
+
+impl<PossibleFlagsT: BitBaggable> BinRead for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: for<'a> BinRead<Args<'a> = ()>,
+{
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        PossibleFlagsT::ReprT::read_options(reader, endian, ()).map(BitBag::new)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BinWrite for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: for<'a> BinWrite<Args<'a> = ()>,
+{
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.repr.write_options(writer, endian, ())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> ReadEndian for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: ReadEndian,
+{
+    const ENDIAN: EndianKind = <PossibleFlagsT::ReprT as ReadEndian>::ENDIAN;
+}
+
+impl<PossibleFlagsT: BitBaggable> WriteEndian for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: WriteEndian,
+{
+    const ENDIAN: EndianKind = <PossibleFlagsT::ReprT as WriteEndian>::ENDIAN;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::tests::FooFlags;
+    use crate::{BitBag, BitBaggable};
+    use ::binrw::{io::Cursor, BinRead, BinWrite};
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(u16)]
+    enum Wide {
+        Low = 0x0001,
+        High = 0x0100,
+    }
+
+    #[derive(Debug, PartialEq, BinRead, BinWrite)]
+    struct Header {
+        small: BitBag<FooFlags>,
+        #[br(assert(!wide.has_unrecognised_bits()))]
+        wide: BitBag<Wide>,
+    }
+
+    #[test]
+    fn endianness() {
+        let bytes = [0b0000_1001, 0x01, 0x00];
+        let header = Header::read_le(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(header.small, FooFlags::A | FooFlags::D);
+        assert_eq!(header.wide.get(), 0x0001);
+        let header = Header::read_be(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(header.wide.get(), 0x0100);
+
+        let mut out = Cursor::new(Vec::new());
+        header.write_be(&mut out).unwrap();
+        assert_eq!(out.into_inner(), bytes);
+    }
+
+    #[test]
+    fn strict() {
+        let bytes = [0, 0x02, 0x00];
+        assert!(Header::read_le(&mut Cursor::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn fixed_endian() {
+        let bag = BitBag::<FooFlags>::read(&mut Cursor::new(&[0b0000_0110])).unwrap();
+        assert_eq!(bag, FooFlags::B | FooFlags::C);
+    }
+}
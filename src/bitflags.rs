@@ -0,0 +1,125 @@
+//! Conversions to and from [bitflags](https://docs.rs/bitflags) 2.x types, enabled by the `bitflags` feature.
+//!
+//! For migrating gradually, where an enum and a `bitflags!` type describe the same bits.
+//! Both types usually live in your crate, so [`bitflags_pair!`](crate::bitflags_pair) implements the conversions there,
+//! with [`From`] into the bitflags type, and [`TryFrom`] back, which rejects bits the enum doesn't declare.
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use std::convert::TryFrom as _;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! bitflags::bitflags! {
+//!     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//!     struct Perms: u8 {
+//!         const READ = 0b0001;
+//!         const WRITE = 0b0010;
+//!         const EXEC = 0b0100;
+//!     }
+//! }
+//!
+//! bitbag::bitflags_pair!(Perm => Perms);
+//!
+//! assert_eq!(Perms::from(Perm::Read | Perm::Write), Perms::READ | Perms::WRITE);
+//! assert_eq!(BitBag::<Perm>::try_from(Perms::WRITE).unwrap(), BitBag::just(Perm::Write));
+//! assert!(BitBag::<Perm>::try_from(Perms::EXEC).is_err());
+//! ```
+
+use crate::{BitBag, BitBaggable, NonFlagBits};
+use ::bitflags::Flags;
+
+/// The bitflags value with the same bits as `bag`, including any unrecognised bits
+pub fn to_bitflags<PossibleFlagsT, FlagsT>(bag: BitBag<PossibleFlagsT>) -> FlagsT
+where
+    PossibleFlagsT: BitBaggable,
+    FlagsT: Flags<Bits = PossibleFlagsT::ReprT>,
+{
+    FlagsT::from_bits_retain(bag.get())
+}
+
+/// The bag with the same bits as `flags`, failing if any bits aren't declared in the enum
+pub fn from_bitflags<PossibleFlagsT, FlagsT>(
+    flags: FlagsT,
+) -> Result<BitBag<PossibleFlagsT>, NonFlagBits<PossibleFlagsT>>
+where
+    PossibleFlagsT: BitBaggable,
+    FlagsT: Flags<Bits = PossibleFlagsT::ReprT>,
+{
+    BitBag::new_strict(flags.bits())
+}
+
+/// The bag with the same bits as `flags`, permitting (and preserving) unrecognised bits, as [`BitBag::new`] does
+pub fn from_bitflags_retain<PossibleFlagsT, FlagsT>(flags: FlagsT) -> BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT: BitBaggable,
+    FlagsT: Flags<Bits = PossibleFlagsT::ReprT>,
+{
+    BitBag::new(flags.bits())
+}
+
+/// Implement [`From`] a bag for a bitflags type, and [`TryFrom`] the bitflags type for the bag.
+/// ```ignore
+/// bitbag::bitflags_pair! {
+///     Perm => Perms,
+///     Mode => Modes,
+/// }
+/// ```
+#[macro_export]
+macro_rules! bitflags_pair {
+    ($($flags:ty => $bitflags:ty),* $(,)?) => {$(
+        impl ::core::convert::From<$crate::BitBag<$flags>> for $bitflags {
+            fn from(bag: $crate::BitBag<$flags>) -> Self {
+                $crate::bitflags::to_bitflags(bag)
+            }
+        }
+
+        impl ::core::convert::TryFrom<$bitflags> for $crate::BitBag<$flags> {
+            type Error = $crate::NonFlagBits<$flags>;
+
+            fn try_from(flags: $bitflags) -> ::core::result::Result<Self, Self::Error> {
+                $crate::bitflags::from_bitflags(flags)
+            }
+        }
+    )*};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+    use std::convert::TryFrom as _;
+
+    ::bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Foos: u8 {
+            const A = 0b0001;
+            const B = 0b0010;
+            const C = 0b0100;
+            const D = 0b1000;
+        }
+    }
+
+    crate::bitflags_pair!(FooFlags => Foos);
+
+    #[test]
+    fn pair() {
+        assert_eq!(Foos::from(A | C), Foos::A | Foos::C);
+        assert_eq!(BitBag::try_from(Foos::B | Foos::D).unwrap(), B | D);
+        assert_eq!(Foos::from(BitBag::<FooFlags>::all()), Foos::all());
+    }
+
+    #[test]
+    fn unrecognised() {
+        let flags = Foos::from_bits_retain(0b1_0001);
+        let e = BitBag::<FooFlags>::try_from(flags).unwrap_err();
+        assert_eq!(e.unrecognised(), 0b1_0000);
+        let bag = from_bitflags_retain::<FooFlags, _>(flags);
+        assert_eq!(bag.get(), 0b1_0001);
+        assert_eq!(to_bitflags::<_, Foos>(bag), flags);
+    }
+}
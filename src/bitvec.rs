@@ -0,0 +1,109 @@
+//! [bitvec](https://docs.rs/bitvec) support, enabled by the `bitvec` feature.
+//!
+//! A [`BitBag`] converts to and from a [`BitArray`] over its inner primitive, and can be viewed as a [`BitSlice`],
+//! with bit `n` of the slice being bit `n` of the primitive.
+//! These keep any unrecognised bits, so round trips are lossless.
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use bitvec::prelude::*;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0100,
+//! }
+//!
+//! let mut bag = Flags::A | Flags::B;
+//! assert_eq!(bag.as_bitslice().iter_ones().collect::<Vec<_>>(), [0, 2]);
+//!
+//! bag.as_mut_bitslice().set(2, false);
+//! let array = BitArray::<u8, Lsb0>::from(bag);
+//! assert_eq!(BitBag::<Flags>::from(array).get(), 0b0001);
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::bitvec::{array::BitArray, order::Lsb0, slice::BitSlice, store::BitStore};
+use num::{One as _, PrimInt as _};
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>>
+    for BitArray<PossibleFlagsT::ReprT, Lsb0>
+where
+    PossibleFlagsT::ReprT: BitStore,
+{
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        BitArray::new(bag.repr)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<BitArray<PossibleFlagsT::ReprT, Lsb0>>
+    for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: BitStore,
+{
+    fn from(array: BitArray<PossibleFlagsT::ReprT, Lsb0>) -> Self {
+        BitBag::new(array.into_inner())
+    }
+}
+
+/// bitvec views
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: BitStore,
+{
+    /// View the inner primitive as bits, least significant first
+    pub fn as_bitslice(&self) -> &BitSlice<PossibleFlagsT::ReprT, Lsb0> {
+        BitSlice::from_element(&self.repr)
+    }
+
+    /// Mutably view the inner primitive as bits, least significant first
+    pub fn as_mut_bitslice(&mut self) -> &mut BitSlice<PossibleFlagsT::ReprT, Lsb0> {
+        BitSlice::from_element_mut(&mut self.repr)
+    }
+
+    /// Load a bag from up to the width of the inner primitive of bits, least significant first.
+    ///
+    /// Returns [`None`] if `bits` is too long.
+    pub fn from_bitslice(bits: &BitSlice<PossibleFlagsT::ReprT, Lsb0>) -> Option<Self> {
+        if bits.len() > Self::ENCODED_LEN * 8 {
+            return None;
+        }
+        let mut bag = BitBag::empty();
+        for ix in bits.iter_ones() {
+            bag.set_raw(PossibleFlagsT::ReprT::one().unsigned_shl(ix as u32));
+        }
+        Some(bag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::bitvec::prelude::*;
+
+    #[test]
+    fn views() {
+        let mut bag = A | D;
+        assert_eq!(bag.as_bitslice().iter_ones().collect::<Vec<_>>(), [0, 3]);
+        bag.as_mut_bitslice().set(1, true);
+        assert_eq!(bag, A | B | D);
+    }
+
+    #[test]
+    fn conversions() {
+        let bag = BitBag::<FooFlags>::new(0b1000_0010);
+        let array = BitArray::<u8, Lsb0>::from(bag);
+        assert_eq!(array.into_inner(), 0b1000_0010);
+        assert_eq!(BitBag::<FooFlags>::from(array), bag);
+
+        let bits = bits![u8, Lsb0; 0, 1, 1];
+        assert_eq!(BitBag::<FooFlags>::from_bitslice(bits), Some(B | C));
+        let long = bits![u8, Lsb0; 1; 9];
+        assert_eq!(BitBag::<FooFlags>::from_bitslice(long), None);
+        assert_eq!(
+            BitBag::<FooFlags>::from_bitslice(&long[..0]),
+            Some(BitBag::empty())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
 
 use crate::{BitBag, BitBaggable};
 
@@ -158,3 +158,72 @@ mod tests {
         assert!(!bag.is_set(D));
     }
 }
+
+/// Raw masks from legacy code, only for the primitive which is the enum's repr.
+///
+/// `|` only sets the bits which are defined in the enum, like [`BitBag::new_masked`], so a bad mask can't smuggle
+/// unrecognised bits in. Use [`BitBag::set_raw`] to set bits unchecked.
+/// `&` can only clear bits, so takes the mask as it is.
+mod rhs_is_repr {
+    use super::*;
+    use crate::mask;
+
+    macro_rules! impl_repr_ops {
+        ($($repr:ty),* $(,)?) => {$(
+            impl<PossibleFlagsT: BitBaggable<ReprT = $repr>> BitAnd<$repr> for BitBag<PossibleFlagsT> {
+                type Output = BitBag<PossibleFlagsT>;
+
+                fn bitand(self, rhs: $repr) -> Self::Output {
+                    Self::new(self.repr & rhs)
+                }
+            }
+
+            impl<PossibleFlagsT: BitBaggable<ReprT = $repr>> BitAndAssign<$repr> for BitBag<PossibleFlagsT> {
+                fn bitand_assign(&mut self, rhs: $repr) {
+                    *self = self.bitand(rhs);
+                }
+            }
+
+            impl<PossibleFlagsT: BitBaggable<ReprT = $repr>> BitOr<$repr> for BitBag<PossibleFlagsT> {
+                type Output = BitBag<PossibleFlagsT>;
+
+                fn bitor(self, rhs: $repr) -> Self::Output {
+                    Self::new(self.repr | (rhs & mask::<PossibleFlagsT>()))
+                }
+            }
+
+            impl<PossibleFlagsT: BitBaggable<ReprT = $repr>> BitOrAssign<$repr> for BitBag<PossibleFlagsT> {
+                fn bitor_assign(&mut self, rhs: $repr) {
+                    *self = self.bitor(rhs);
+                }
+            }
+        )*};
+    }
+
+    impl_repr_ops!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+    #[cfg(test)]
+    mod tests {
+        use crate::tests::FooFlags::*;
+        use crate::BitBag;
+
+        #[test]
+        fn test_or() {
+            let bag = (A | B) | 0b0100_u8;
+            assert_eq!(bag, A | B | C);
+            let mut bag = BitBag::just(A);
+            bag |= 0b1111_1000;
+            assert_eq!(bag, A | D);
+            assert!(!bag.has_unrecognised_bits());
+        }
+
+        #[test]
+        fn test_and() {
+            let bag = (A | B | C) & 0b0101_u8;
+            assert_eq!(bag, A | C);
+            let mut bag = BitBag::<crate::tests::FooFlags>::new(0b1000_0011);
+            bag &= 0b1000_0001;
+            assert_eq!(bag.get(), 0b1000_0001);
+        }
+    }
+}
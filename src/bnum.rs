@@ -0,0 +1,54 @@
+//! Reprs wider than `u128`, backed by [bnum](https://docs.rs/bnum), enabled by the `bnum` feature.
+//!
+//! Add `#[bitbag(bnum = "U256")]` to the derive, and give each variant the index of its bit,
+//! rather than its mask; the `#[repr(..)]` only needs to be wide enough for the indices.
+//! ```
+//! use bitbag::{BitBag, BitBaggable};
+//!
+//! #[derive(Debug, Clone, Copy, BitBaggable)]
+//! #[bitbag(bnum = "U256")]
+//! #[repr(u8)]
+//! enum Capability {
+//!     Mint = 0,
+//!     Burn = 1,
+//!     Pause = 255,
+//! }
+//!
+//! let mut caps = BitBag::<Capability>::empty();
+//! caps.set(Capability::Mint).set(Capability::Pause);
+//! assert_eq!(caps.get(), bitbag::bnum::U256::power_of_two(0) | bitbag::bnum::U256::power_of_two(255));
+//! assert_eq!(caps.to_string(), "Mint | Pause");
+//! ```
+
+pub use ::bnum::types::{U1024, U2048, U256, U4096, U512, U8192};
+
+#[cfg(test)]
+mod tests {
+    use super::U512;
+    use crate as bitbag;
+    use crate::{BitBag, BitBaggable};
+
+    #[derive(Debug, Clone, Copy, PartialEq, BitBaggable)]
+    #[bitbag(bnum = "U512")]
+    #[repr(u16)]
+    enum Wide {
+        Low = 0,
+        Mid = 200,
+        High = 511,
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(Wide::Mid.into_repr(), U512::power_of_two(200));
+        let mut bag = BitBag::<Wide>::empty();
+        bag.set(Wide::Low).set(Wide::High);
+        assert!(bag.is_set(Wide::High));
+        assert!(!bag.is_set(Wide::Mid));
+        assert_eq!(bag.into_iter().collect::<Vec<_>>(), [Wide::Low, Wide::High]);
+        assert_eq!(BitBag::<Wide>::ENCODED_LEN, 64);
+
+        let bag = BitBag::<Wide>::new(U512::power_of_two(3));
+        assert!(bag.has_unrecognised_bits());
+        assert!(BitBag::<Wide>::new_strict(bag.get()).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! [borsh](https://docs.rs/borsh) support, enabled by the `borsh` feature.
+//!
+//! A [`BitBag`] is encoded as its inner primitive.
+//! Decoding fails with [`ErrorKind::InvalidData`] if there are bits set which aren't defined in the enum.
+
+use crate::{BitBag, BitBaggable};
+use ::borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+use core::fmt::Binary;
+
+impl<PossibleFlagsT: BitBaggable> BorshSerialize for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: BorshSerialize,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.repr.serialize(writer)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BorshDeserialize for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: BorshDeserialize + Binary,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let repr = PossibleFlagsT::ReprT::deserialize_reader(reader)?;
+        Self::new_strict(repr).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::{BitBag, BitBaggable};
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(u16)]
+    enum Wide {
+        Low = 0x0001,
+        High = 0x0100,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bag = BitBag::<Wide>::of([Wide::Low, Wide::High]);
+        let bytes = ::borsh::to_vec(&bag).unwrap();
+        assert_eq!(bytes, [0x01, 0x01]);
+        assert_eq!(::borsh::from_slice::<BitBag<Wide>>(&bytes).unwrap(), bag);
+    }
+
+    #[test]
+    fn strict() {
+        let e = ::borsh::from_slice::<BitBag<Wide>>(&[0x02, 0x00]).unwrap_err();
+        assert_eq!(e.kind(), ::borsh::io::ErrorKind::InvalidData);
+    }
+}
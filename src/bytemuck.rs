@@ -0,0 +1,41 @@
+//! [bytemuck](https://docs.rs/bytemuck) support, enabled by the `bytemuck` feature.
+//!
+//! [`BitBag`] is `repr(transparent)` over its primitive, so it has the same size, alignment and valid bit patterns.
+//! Any bit pattern is a valid bag, with unrecognised bits preserved as by [`BitBag::new`].
+
+use crate::{BitBag, BitBaggable};
+use ::bytemuck::{Pod, Zeroable};
+
+// SAFETY: `BitBag` is `repr(transparent)` over `ReprT`, and all zeroes is the empty bag.
+unsafe impl<PossibleFlagsT: BitBaggable> Zeroable for BitBag<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Zeroable
+{
+}
+
+// SAFETY: `BitBag` is `repr(transparent)` over `ReprT`, has no padding, and every bit pattern is valid.
+unsafe impl<PossibleFlagsT: BitBaggable> Pod for BitBag<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Pod
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+    use std::mem::{align_of, size_of};
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<BitBag<FooFlags>>(), size_of::<u8>());
+        assert_eq!(align_of::<BitBag<FooFlags>>(), align_of::<u8>());
+    }
+
+    #[test]
+    fn cast() {
+        let bytes = [0b0000_0001u8, 0b0000_1100];
+        let bags: &[BitBag<FooFlags>] = ::bytemuck::cast_slice(&bytes);
+        assert_eq!(bags, [BitBag::new(0b0000_0001), FooFlags::C | FooFlags::D]);
+        assert_eq!(::bytemuck::bytes_of(&bags[1]), [0b0000_1100]);
+        assert!(<BitBag<FooFlags> as ::bytemuck::Zeroable>::zeroed().is_empty());
+    }
+}
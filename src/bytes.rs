@@ -0,0 +1,268 @@
+use crate::{BitBag, BitBaggable, NonFlagBits};
+use core::{
+    fmt::{self, Binary, Debug, Display},
+    mem::size_of,
+};
+use num::{
+    traits::{FromBytes, ToBytes},
+    PrimInt,
+};
+
+/// Byte conversions, sized to the enum's repr
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// The bits as a little-endian byte array
+    pub fn to_le_bytes(&self) -> <PossibleFlagsT::ReprT as ToBytes>::Bytes
+    where
+        PossibleFlagsT::ReprT: ToBytes,
+    {
+        self.repr.to_le_bytes()
+    }
+
+    /// The bits as a big-endian byte array
+    pub fn to_be_bytes(&self) -> <PossibleFlagsT::ReprT as ToBytes>::Bytes
+    where
+        PossibleFlagsT::ReprT: ToBytes,
+    {
+        self.repr.to_be_bytes()
+    }
+
+    /// New bag from a little-endian byte array, permitting (and preserving) unrecognised bits.
+    ///
+    /// Follow with [`BitBag::new_strict`] to reject them.
+    pub fn from_le_bytes(bytes: &<PossibleFlagsT::ReprT as FromBytes>::Bytes) -> Self
+    where
+        PossibleFlagsT::ReprT: FromBytes,
+    {
+        Self::new(PossibleFlagsT::ReprT::from_le_bytes(bytes))
+    }
+
+    /// New bag from a big-endian byte array, permitting (and preserving) unrecognised bits.
+    ///
+    /// Follow with [`BitBag::new_strict`] to reject them.
+    pub fn from_be_bytes(bytes: &<PossibleFlagsT::ReprT as FromBytes>::Bytes) -> Self
+    where
+        PossibleFlagsT::ReprT: FromBytes,
+    {
+        Self::new(PossibleFlagsT::ReprT::from_be_bytes(bytes))
+    }
+}
+
+/// A compact encoding which doesn't need serde.
+///
+/// The layout is stable: the inner primitive in little-endian two's complement,
+/// taking exactly [`BitBag::ENCODED_LEN`] bytes, with no header.
+///
+/// For a `usize` or `isize` repr, that's the target's pointer width,
+/// so bags encoded on one target can only be decoded on targets of the same width.
+/// Use a fixed-width repr for bags which cross between them.
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// The number of bytes written by [`BitBag::encode_into`], and read by [`BitBag::decode_from`],
+    /// which depends on the target for a `usize` or `isize` repr
+    pub const ENCODED_LEN: usize = size_of::<PossibleFlagsT::ReprT>();
+
+    /// Write the bag to the start of `buf`, returning the number of bytes written.
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, BufferTooShort> {
+        let available = buf.len();
+        let buf = buf
+            .get_mut(..Self::ENCODED_LEN)
+            .ok_or(BufferTooShort::new(Self::ENCODED_LEN, available))?;
+        repr_to_bytes(self.repr, buf, true);
+        Ok(Self::ENCODED_LEN)
+    }
+
+    /// Read a bag from the start of `buf`, as written by [`BitBag::encode_into`].
+    ///
+    /// Any bytes after the first [`BitBag::ENCODED_LEN`] are ignored.
+    /// Fails if there are bits set which aren't defined in the enum.
+    pub fn decode_from(buf: &[u8]) -> Result<Self, DecodeError<PossibleFlagsT>> {
+        let too_short = BufferTooShort::new(Self::ENCODED_LEN, buf.len());
+        let buf = buf
+            .get(..Self::ENCODED_LEN)
+            .ok_or(DecodeError::BufferTooShort(too_short))?;
+        Self::new_strict(repr_from_bytes(buf, true)).map_err(DecodeError::NonFlagBits)
+    }
+}
+
+/// Write `repr` to `buf`, least significant byte first if `little`.
+///
+/// This goes bit-by-bit, which works for signed reprs too.
+pub(crate) fn repr_to_bytes<ReprT: PrimInt>(repr: ReprT, buf: &mut [u8], little: bool) {
+    let len = buf.len();
+    for (ix, byte) in buf.iter_mut().enumerate() {
+        let shift = match little {
+            true => ix * 8,
+            false => (len - 1 - ix) * 8,
+        };
+        *byte = 0;
+        for bit in 0..8 {
+            if repr & ReprT::one().unsigned_shl((shift + bit) as u32) != ReprT::zero() {
+                *byte |= 1 << bit;
+            }
+        }
+    }
+}
+
+/// Read a repr from `bytes`, least significant byte first if `little`, see [`repr_to_bytes`]
+pub(crate) fn repr_from_bytes<ReprT: PrimInt>(bytes: &[u8], little: bool) -> ReprT {
+    let mut repr = ReprT::zero();
+    for (ix, byte) in bytes.iter().enumerate() {
+        let shift = match little {
+            true => ix * 8,
+            false => (bytes.len() - 1 - ix) * 8,
+        };
+        for bit in 0..8 {
+            if byte & (1 << bit) != 0 {
+                repr = repr | ReprT::one().unsigned_shl((shift + bit) as u32);
+            }
+        }
+    }
+    repr
+}
+
+/// The error returned when a buffer is too short to encode or decode a [`BitBag`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooShort {
+    needed: usize,
+    available: usize,
+}
+
+impl BufferTooShort {
+    pub(crate) fn new(needed: usize, available: usize) -> Self {
+        Self { needed, available }
+    }
+
+    /// The number of bytes needed
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// The number of bytes in the buffer
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl core::error::Error for BufferTooShort {}
+
+impl Display for BufferTooShort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer of {} bytes is too short, {} are needed",
+            self.available, self.needed
+        )
+    }
+}
+
+/// The error returned when decoding a [`BitBag`] fails
+#[non_exhaustive]
+pub enum DecodeError<PossibleFlagsT: BitBaggable> {
+    /// There were fewer than [`BitBag::ENCODED_LEN`] bytes
+    BufferTooShort(BufferTooShort),
+    /// The bytes had bits set which aren't defined in the enum
+    NonFlagBits(NonFlagBits<PossibleFlagsT>),
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for DecodeError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BufferTooShort(e) => f.debug_tuple("BufferTooShort").field(e).finish(),
+            DecodeError::NonFlagBits(e) => f.debug_tuple("NonFlagBits").field(e).finish(),
+        }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for DecodeError<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Binary + Debug
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for DecodeError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BufferTooShort(e) => Display::fmt(e, f),
+            DecodeError::NonFlagBits(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::{BitBag, BitBaggable, DecodeError};
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(u16)]
+    enum Wide {
+        Low = 0x0001,
+        High = 0x0100,
+    }
+
+    #[test]
+    fn endianness() {
+        let bag = BitBag::<Wide>::new(0x0102);
+        assert_eq!(bag.to_le_bytes(), [0x02, 0x01]);
+        assert_eq!(bag.to_be_bytes(), [0x01, 0x02]);
+        assert_eq!(BitBag::<Wide>::from_le_bytes(&[0x02, 0x01]).get(), 0x0102);
+        assert_eq!(BitBag::<Wide>::from_be_bytes(&[0x01, 0x02]).get(), 0x0102);
+    }
+
+    #[test]
+    fn signed() {
+        use super::{repr_from_bytes, repr_to_bytes};
+
+        let mut buf = [0; 4];
+        repr_to_bytes(i32::MIN | 1, &mut buf, false);
+        assert_eq!(buf, [0x80, 0x00, 0x00, 0x01]);
+        assert_eq!(repr_from_bytes::<i32>(&buf, false), i32::MIN | 1);
+        repr_to_bytes(i32::MIN | 1, &mut buf, true);
+        assert_eq!(buf, [0x01, 0x00, 0x00, 0x80]);
+        assert_eq!(repr_from_bytes::<i32>(&buf, true), i32::MIN | 1);
+    }
+
+    #[test]
+    fn encode() {
+        let mut buf = [0xFF; 3];
+        let bag = BitBag::<Wide>::new(0x0101);
+        assert_eq!(bag.encode_into(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [0x01, 0x01, 0xFF]);
+        assert_eq!(BitBag::<Wide>::decode_from(&buf).unwrap(), bag);
+
+        let e = bag.encode_into(&mut buf[..1]).unwrap_err();
+        assert_eq!((e.needed(), e.available()), (2, 1));
+    }
+
+    #[test]
+    fn pointer_width() {
+        #[derive(Debug, BitBaggable)]
+        #[repr(usize)]
+        enum Native {
+            Top = 1 << 31,
+        }
+
+        let mut buf = [0; 16];
+        let bag = BitBag::<Native>::just(Native::Top);
+        let len = bag.encode_into(&mut buf).unwrap();
+        assert_eq!(len, core::mem::size_of::<usize>());
+        assert_eq!(buf[..len], (1usize << 31).to_le_bytes());
+        assert_eq!(BitBag::<Native>::decode_from(&buf).unwrap(), bag);
+    }
+
+    #[test]
+    fn decode_strict() {
+        assert!(matches!(
+            BitBag::<Wide>::decode_from(&[0x02, 0x00]),
+            Err(DecodeError::NonFlagBits(_))
+        ));
+        assert!(matches!(
+            BitBag::<Wide>::decode_from(&[0x01]),
+            Err(DecodeError::BufferTooShort(_))
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+//! A bag with single-threaded interior mutability, see [`BitBagCell`].
+
+use crate::{BitBag, BitBaggable};
+use core::{
+    cell::Cell,
+    fmt::{self, Debug},
+};
+
+/// A [`BitBag`] which can be modified through a shared reference, on a single thread.
+///
+/// Like [`Cell`], this never hands out references to the bag, only copies of it.
+pub struct BitBagCell<PossibleFlagsT: BitBaggable> {
+    inner: Cell<BitBag<PossibleFlagsT>>,
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBagCell<PossibleFlagsT> {
+    pub const fn new(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self {
+            inner: Cell::new(bag),
+        }
+    }
+
+    pub fn into_inner(self) -> BitBag<PossibleFlagsT> {
+        self.inner.into_inner()
+    }
+
+    pub fn get(&self) -> BitBag<PossibleFlagsT> {
+        self.inner.get()
+    }
+
+    /// Replace the whole bag
+    pub fn set(&self, bag: BitBag<PossibleFlagsT>) {
+        self.inner.set(bag)
+    }
+
+    /// Replace the whole bag, returning the previous one
+    pub fn replace(&self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        self.inner.replace(bag)
+    }
+
+    /// Clear the bag, returning the bits which were set
+    pub fn take(&self) -> BitBag<PossibleFlagsT> {
+        self.replace(BitBag::empty())
+    }
+
+    pub fn set_flag(&self, flag: PossibleFlagsT) {
+        self.update(|mut bag| *bag.set(flag));
+    }
+
+    pub fn unset_flag(&self, flag: PossibleFlagsT) {
+        self.update(|mut bag| *bag.unset(flag));
+    }
+
+    /// Replace the bag with the result of `f`, returning the new bag
+    pub fn update(
+        &self,
+        f: impl FnOnce(BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT>,
+    ) -> BitBag<PossibleFlagsT> {
+        let bag = f(self.get());
+        self.set(bag);
+        bag
+    }
+
+    pub fn get_mut(&mut self) -> &mut BitBag<PossibleFlagsT> {
+        self.inner.get_mut()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Default for BitBagCell<PossibleFlagsT> {
+    fn default() -> Self {
+        Self::new(BitBag::empty())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>> for BitBagCell<PossibleFlagsT> {
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self::new(bag)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for BitBagCell<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for BitBagCell<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BitBagCell").field(&self.get()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitBagCell;
+    use crate::tests::FooFlags::{self, *};
+
+    #[test]
+    fn shared() {
+        let cell = BitBagCell::<FooFlags>::default();
+        let callbacks = [&cell, &cell];
+        callbacks[0].set_flag(A);
+        callbacks[1].set_flag(C);
+        assert_eq!(cell.get(), A | C);
+        cell.unset_flag(A);
+        assert_eq!(cell.update(|bag| bag | B), B | C);
+        assert_eq!(cell.replace(A | D), B | C);
+        assert_eq!(cell.take(), A | D);
+        assert!(cell.into_inner().is_empty());
+    }
+}
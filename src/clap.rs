@@ -0,0 +1,206 @@
+//! [clap](https://docs.rs/clap) support, enabled by the `clap` feature.
+//!
+//! [`value_parser`] parses a comma separated list of flag names, like `--features read,write`, straight into a bag.
+//! The flag names are offered as possible values, so they appear in `--help` and shell completions,
+//! and unknown names are reported with clap's usual "invalid value" error, including any suggestion.
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use clap::{Arg, Command};
+//!
+//! #[derive(Debug, Clone, Copy, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Feature {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! let cmd = Command::new("app").arg(
+//!     Arg::new("features")
+//!         .long("features")
+//!         .value_parser(bitbag::clap::value_parser::<Feature>()),
+//! );
+//! let matches = cmd.try_get_matches_from(["app", "--features", "Read,Write"]).unwrap();
+//! let bag = *matches.get_one::<BitBag<Feature>>("features").unwrap();
+//! assert_eq!(bag, Feature::Read | Feature::Write);
+//! ```
+//!
+//! With `ignore_case(true)` on the argument, names are matched regardless of ASCII case, so `--features read,write` works too.
+
+use crate::{
+    parse::{by_name, parse_token},
+    BitBag, BitBaggable, ParseError,
+};
+use ::clap::{
+    builder::{PossibleValue, TypedValueParser, ValueParser},
+    error::{ContextKind, ContextValue, ErrorKind},
+    Arg, Command, Error,
+};
+use std::{ffi::OsStr, fmt::Binary, marker::PhantomData};
+
+/// A [`ValueParser`] for a comma separated list of flag names (or numbers), see [`BitBagParser`]
+pub fn value_parser<PossibleFlagsT>() -> ValueParser
+where
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: Binary + Send + Sync,
+{
+    BitBagParser::<PossibleFlagsT>::new().into()
+}
+
+/// The names of every flag, as clap [`PossibleValue`]s, for help text and completions
+pub fn possible_values<PossibleFlagsT: BitBaggable>() -> impl Iterator<Item = PossibleValue> {
+    PossibleFlagsT::VARIANTS
+        .iter()
+        .map(|(name, _, _)| PossibleValue::new(*name))
+}
+
+/// Parses a comma separated list of flag names (or numbers) into a [`BitBag`].
+///
+/// This is the [`TypedValueParser`] behind [`value_parser`], for use with its combinators, like `map`.
+pub struct BitBagParser<PossibleFlagsT> {
+    flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT> BitBagParser<PossibleFlagsT> {
+    pub fn new() -> Self {
+        Self { flags: PhantomData }
+    }
+}
+
+impl<PossibleFlagsT> Default for BitBagParser<PossibleFlagsT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<PossibleFlagsT> Clone for BitBagParser<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<PossibleFlagsT> TypedValueParser for BitBagParser<PossibleFlagsT>
+where
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: Binary + Send + Sync,
+{
+    type Value = BitBag<PossibleFlagsT>;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+        let ignore_case = arg.is_some_and(Arg::is_ignore_case_set);
+        let mut bag = BitBag::empty();
+        for token in value.split(',') {
+            let repr = parse_token::<PossibleFlagsT>(token.trim(), |name| {
+                by_name::<PossibleFlagsT>(name).or_else(|| {
+                    PossibleFlagsT::VARIANTS
+                        .iter()
+                        .find(|(candidate, _, _)| {
+                            ignore_case && candidate.eq_ignore_ascii_case(name)
+                        })
+                        .map(|(_, _, repr)| *repr)
+                })
+            })
+            .map_err(|e| invalid_value(cmd, arg, token.trim(), e))?;
+            bag.set_raw(repr);
+        }
+        Ok(bag)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(possible_values::<PossibleFlagsT>()))
+    }
+}
+
+/// Unknown names get the same error clap reports for values outside a `PossibleValuesParser`, with any suggestion
+fn invalid_value<PossibleFlagsT: BitBaggable>(
+    cmd: &Command,
+    arg: Option<&Arg>,
+    token: &str,
+    e: ParseError<PossibleFlagsT>,
+) -> Error
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    let arg = arg.map_or_else(|| String::from("..."), ToString::to_string);
+    if !matches!(e, ParseError::UnknownName(_)) {
+        let message = format!("invalid value '{token}' for '{arg}': {e}\n");
+        return Error::raw(ErrorKind::ValueValidation, message).with_cmd(cmd);
+    }
+    let mut error = Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
+    error.insert(ContextKind::InvalidArg, ContextValue::String(arg));
+    error.insert(
+        ContextKind::InvalidValue,
+        ContextValue::String(String::from(token)),
+    );
+    let names = PossibleFlagsT::VARIANTS
+        .iter()
+        .map(|(name, _, _)| String::from(*name))
+        .collect();
+    error.insert(ContextKind::ValidValue, ContextValue::Strings(names));
+    if let Some(suggestion) = e.suggestion() {
+        error.insert(
+            ContextKind::SuggestedValue,
+            ContextValue::String(String::from(suggestion)),
+        );
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    fn command(ignore_case: bool) -> Command {
+        Command::new("app").arg(
+            Arg::new("flags")
+                .long("flags")
+                .ignore_case(ignore_case)
+                .value_parser(value_parser::<FooFlags>()),
+        )
+    }
+
+    fn parse(ignore_case: bool, value: &str) -> Result<BitBag<FooFlags>, Error> {
+        command(ignore_case)
+            .try_get_matches_from(["app", "--flags", value])
+            .map(|matches| *matches.get_one::<BitBag<FooFlags>>("flags").unwrap())
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(parse(false, "A,C").unwrap(), A | C);
+        assert_eq!(parse(false, "B, 0x8").unwrap(), B | D);
+        assert_eq!(parse(true, "a,d").unwrap(), A | D);
+        assert!(parse(false, "a").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let e = parse(false, "A,E").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidValue);
+        let rendered = e.to_string();
+        assert!(rendered.contains("invalid value 'E' for '--flags <flags>'"));
+        assert!(rendered.contains("[possible values: A, B, C, D]"));
+
+        let e = parse(false, "A,,B").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::ValueValidation);
+        let e = parse(false, "0x10").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::ValueValidation);
+        assert!(e
+            .to_string()
+            .contains("invalid value '0x10' for '--flags <flags>': The bits"));
+    }
+
+    #[test]
+    fn help() {
+        let help = command(false).render_help().to_string();
+        assert!(help.contains("[possible values: A, B, C, D]"));
+    }
+}
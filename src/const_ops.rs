@@ -0,0 +1,176 @@
+//! Bag literals with [`bag!`](crate::bag), and set operations on raw reprs in const contexts,
+//! until const trait methods let [`BitBag`](crate::BitBag) do them.
+//!
+//! The set operations are macros rather than `const fn`s, because a generic `const fn` can't operate on [`BitBaggable::ReprT`](crate::BitBaggable::ReprT).
+//! Each checks its operands against the bits of the enum's variants,
+//! so bits which aren't defined in the enum are a compile error in a `const`, and a panic elsewhere.
+//! ```
+//! use bitbag::BitBaggable;
+//!
+//! #[derive(BitBaggable)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b001,
+//!     Write = 0b010,
+//!     Exec = 0b100,
+//! }
+//!
+//! const READ_WRITE: u8 = bitbag::const_union!(Perm, Perm::Read as u8, Perm::Write as u8);
+//! const SHARED: u8 = bitbag::const_intersection!(Perm, READ_WRITE, 0b110);
+//! const _: () = assert!(bitbag::const_contains!(Perm, READ_WRITE, SHARED));
+//! assert_eq!(SHARED, Perm::Write as u8);
+//! ```
+//! ```compile_fail
+//! # use bitbag::BitBaggable;
+//! # #[derive(BitBaggable)]
+//! # #[repr(u8)]
+//! # enum Perm {
+//! #     Read = 0b001,
+//! # }
+//! const BAD: u8 = bitbag::const_union!(Perm, Perm::Read as u8, 0b1000);
+//! ```
+
+/// The bits set in either `a` or `b`, which must only contain bits defined in the enum.
+#[macro_export]
+macro_rules! const_union {
+    ($flags:ty, $a:expr, $b:expr $(,)?) => {{
+        let (a, b) = ($a, $b);
+        ::core::assert!(
+            <$flags>::__bitbag_is_valid(a) && <$flags>::__bitbag_is_valid(b),
+            "bitbag: operand has bits which aren't defined in the enum"
+        );
+        a | b
+    }};
+}
+
+/// The bits set in both `a` and `b`, which must only contain bits defined in the enum.
+#[macro_export]
+macro_rules! const_intersection {
+    ($flags:ty, $a:expr, $b:expr $(,)?) => {{
+        let (a, b) = ($a, $b);
+        ::core::assert!(
+            <$flags>::__bitbag_is_valid(a) && <$flags>::__bitbag_is_valid(b),
+            "bitbag: operand has bits which aren't defined in the enum"
+        );
+        a & b
+    }};
+}
+
+/// Whether every bit set in `needle` is also set in `haystack`, both of which must only contain bits defined in the enum.
+#[macro_export]
+macro_rules! const_contains {
+    ($flags:ty, $haystack:expr, $needle:expr $(,)?) => {{
+        let (haystack, needle) = ($haystack, $needle);
+        ::core::assert!(
+            <$flags>::__bitbag_is_valid(haystack) && <$flags>::__bitbag_is_valid(needle),
+            "bitbag: operand has bits which aren't defined in the enum"
+        );
+        haystack & needle == needle
+    }};
+}
+
+/// A [`BitBag`](crate::BitBag) of the given flags, which can be evaluated at compile time, like in a `static`.
+///
+/// Flags are either written out in full, as `bag!(Perm::Read | Perm::Write)`,
+/// or after the path of the enum, as `bag!(perms::Perm: Read | Write)`, for enums in other modules.
+/// ```
+/// use bitbag::{bag, BitBag, BitBaggable};
+///
+/// #[derive(BitBaggable)]
+/// #[repr(u8)]
+/// enum FilePerm {
+///     Read = 0b001,
+///     Write = 0b010,
+///     Exec = 0b100,
+/// }
+///
+/// static READ_WRITE: BitBag<FilePerm> = bag!(FilePerm::Read | FilePerm::Write);
+///
+/// match BitBag::<FilePerm>::new(0b011) {
+///     perms if perms == READ_WRITE => {}
+///     _ => unreachable!(),
+/// }
+///
+/// mod perms {
+///     #[derive(bitbag::BitBaggable)]
+///     #[repr(u8)]
+///     pub enum Perm {
+///         Read = 0b01,
+///         Write = 0b10,
+///     }
+/// }
+///
+/// const READ: BitBag<perms::Perm> = bag!(perms::Perm: Read);
+/// assert_eq!(READ.get(), 0b01);
+/// assert_eq!(bag!(perms::Perm: Read | Write).get(), 0b11);
+/// ```
+/// ```compile_fail
+/// # use bitbag::BitBaggable;
+/// # #[derive(BitBaggable)]
+/// # #[repr(u8)]
+/// # enum Perm {
+/// #     Read = 0b001,
+/// # }
+/// # #[derive(BitBaggable)]
+/// # #[repr(u8)]
+/// # enum Other {
+/// #     Read = 0b001,
+/// # }
+/// // variants of different enums don't mix
+/// let _ = bitbag::bag!(Perm::Read | Other::Read);
+/// ```
+#[macro_export]
+macro_rules! bag {
+    ($flags:ident :: $first:ident $(| $rest_flags:ident :: $rest:ident)* $(,)?) => {
+        $crate::BitBag::<$flags>::new(
+            ($flags::$first as <$flags as $crate::BitBaggable>::ReprT)
+            $(| {
+                // only accept variants of the first enum
+                let flag: $flags = $rest_flags::$rest;
+                flag as <$flags as $crate::BitBaggable>::ReprT
+            })*
+        )
+    };
+    ($flags:path : $first:ident $(| $rest:ident)* $(,)?) => {
+        $crate::BitBag::<$flags>::new(
+            (<$flags>::$first as <$flags as $crate::BitBaggable>::ReprT)
+            $(| (<$flags>::$rest as <$flags as $crate::BitBaggable>::ReprT))*
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags;
+
+    const AB: u8 = crate::const_union!(FooFlags, FooFlags::A as u8, FooFlags::B as u8);
+    const TABLE: [u8; 2] = [AB, crate::const_intersection!(FooFlags, AB, 0b1110)];
+
+    #[test]
+    fn const_ops() {
+        assert_eq!(TABLE, [0b0011, 0b0010]);
+        assert!(crate::const_contains!(FooFlags, AB, 0b0001));
+        assert!(!crate::const_contains!(FooFlags, AB, 0b0100));
+    }
+
+    #[test]
+    fn bag() {
+        use crate::BitBag;
+
+        static ABD: BitBag<FooFlags> = crate::bag!(FooFlags::A | FooFlags::B | FooFlags::D);
+        const C: BitBag<FooFlags> = crate::bag!(FooFlags::C);
+        assert_eq!(ABD.get(), 0b1011);
+        assert_eq!(C.get(), 0b0100);
+
+        static BD: BitBag<FooFlags> = crate::bag!(crate::tests::FooFlags: B | D);
+        assert_eq!(BD.get(), 0b1010);
+        assert_eq!(crate::bag!(FooFlags: C).get(), 0b0100);
+    }
+
+    #[test]
+    #[should_panic = "bits which aren't defined"]
+    fn invalid() {
+        let bits = 0b1_0000;
+        let _ = crate::const_union!(FooFlags, FooFlags::A as u8, bits);
+    }
+}
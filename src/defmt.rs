@@ -0,0 +1,122 @@
+//! [defmt](https://docs.rs/defmt) support, enabled by the `defmt` feature.
+//!
+//! A [`BitBag`] is logged like its [`Display`](std::fmt::Display) implementation, as `A | B`.
+//! The separators are interned, and so are the names of derived enums, see [`BitBaggable::defmt_name`].
+
+use crate::{iter::set_variants, BitBag, BitBaggable};
+#[doc(hidden)]
+pub use ::defmt as __defmt;
+use ::defmt::{Format, Formatter};
+
+impl<PossibleFlagsT: BitBaggable> Format for BitBag<PossibleFlagsT> {
+    fn format(&self, f: Formatter<'_>) {
+        if self.is_empty() {
+            return ::defmt::write!(f, "<unset>");
+        }
+
+        let mut first = true;
+
+        for (name, flag, _) in set_variants(*self, PossibleFlagsT::ORDER) {
+            match first {
+                true => first = false,
+                false => ::defmt::write!(f, " | "),
+            }
+            match flag.defmt_name() {
+                Some(name) => ::defmt::write!(f, "{=istr}", name),
+                None => ::defmt::write!(f, "{=str}", name),
+            }
+        }
+
+        if self.has_unrecognised_bits() {
+            match first {
+                true => ::defmt::write!(f, "<unrecognised bits>"),
+                false => ::defmt::write!(f, " | <unrecognised bits>"),
+            }
+        }
+    }
+}
+
+/// Implements [`BitBaggable::defmt_name`] for the derive.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __defmt_name {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        fn defmt_name(&self) -> ::core::option::Option<$crate::__defmt::Str> {
+            // `intern!` refers to `defmt` by name
+            use $crate::__defmt as defmt;
+            ::core::option::Option::Some(match self {
+                $(Self::$variant => defmt::intern!($name),)*
+            })
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{self as bitbag, BitBag, BitBaggable};
+    use std::sync::Mutex;
+
+    static WRITTEN: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+    #[::defmt::global_logger]
+    struct Logger;
+
+    unsafe impl ::defmt::Logger for Logger {
+        fn acquire() {}
+        unsafe fn flush() {}
+        unsafe fn release() {}
+        unsafe fn write(bytes: &[u8]) {
+            WRITTEN.lock().unwrap().extend_from_slice(bytes);
+        }
+    }
+
+    ::defmt::timestamp!("");
+
+    fn logged(bag: impl ::defmt::Format) -> Vec<u8> {
+        WRITTEN.lock().unwrap().clear();
+        ::defmt::println!("{}", bag);
+        std::mem::take(&mut *WRITTEN.lock().unwrap())
+    }
+
+    fn contains(haystack: &[u8], needle: &str) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+    }
+
+    #[derive(Clone, Copy, BitBaggable)]
+    #[repr(u8)]
+    enum Derived {
+        Readable = 0b01,
+        Writable = 0b10,
+    }
+
+    #[derive(Clone, Copy)]
+    enum Manual {
+        Readable = 0b01,
+        Writable = 0b10,
+    }
+
+    impl BitBaggable for Manual {
+        type ReprT = u8;
+        fn into_repr(self) -> u8 {
+            self as u8
+        }
+        const VARIANTS: &'static [(&'static str, Self, u8)] = &[
+            ("Readable", Manual::Readable, 0b01),
+            ("Writable", Manual::Writable, 0b10),
+        ];
+    }
+
+    #[test]
+    fn interned() {
+        let derived = logged(BitBag::<Derived>::all());
+        assert!(!derived.is_empty());
+        assert!(!contains(&derived, "Readable"));
+        assert!(!contains(&derived, "Writable"));
+
+        let manual = logged(BitBag::<Manual>::all());
+        assert!(contains(&manual, "Readable"));
+        assert!(contains(&manual, "Writable"));
+    }
+}
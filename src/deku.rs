@@ -0,0 +1,86 @@
+//! [deku](https://docs.rs/deku) support, enabled by the `deku` feature.
+//!
+//! A [`BitBag`] is read and written as its inner primitive, forwarding any context,
+//! so `#[deku(endian = "..")]` and `#[deku(bits = ..)]` work on bag fields as they do on integers.
+//! Reading permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+
+use crate::{BitBag, BitBaggable};
+use ::deku::{
+    no_std_io::{Read, Seek, Write},
+    reader::Reader,
+    writer::Writer,
+    DekuError, DekuReader, DekuSize, DekuWriter,
+};
+
+impl<'a, PossibleFlagsT: BitBaggable, Ctx> DekuReader<'a, Ctx> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: DekuReader<'a, Ctx>,
+{
+    fn from_reader_with_ctx<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        ctx: Ctx,
+    ) -> Result<Self, DekuError> {
+        PossibleFlagsT::ReprT::from_reader_with_ctx(reader, ctx).map(BitBag::new)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable, Ctx> DekuWriter<Ctx> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: DekuWriter<Ctx>,
+{
+    fn to_writer<W: Write + Seek>(
+        &self,
+        writer: &mut Writer<W>,
+        ctx: Ctx,
+    ) -> Result<(), DekuError> {
+        self.repr.to_writer(writer, ctx)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> DekuSize for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: DekuSize,
+{
+    const SIZE_BITS: usize = PossibleFlagsT::ReprT::SIZE_BITS;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::tests::FooFlags;
+    use crate::{BitBag, BitBaggable};
+    use ::deku::{DekuContainerRead as _, DekuContainerWrite as _, DekuRead, DekuSize, DekuWrite};
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(u16)]
+    enum Wide {
+        Low = 0x0001,
+        High = 0x0100,
+    }
+
+    #[derive(Debug, PartialEq, DekuRead, DekuWrite)]
+    #[deku(endian = "big")]
+    struct Header {
+        #[deku(bits = 4)]
+        small: BitBag<FooFlags>,
+        #[deku(bits = 4)]
+        version: u8,
+        wide: BitBag<Wide>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = [0b1001_0011, 0x01, 0x00];
+        let ((rest, _), header) = Header::from_bytes((&bytes, 0)).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(header.small, FooFlags::A | FooFlags::D);
+        assert_eq!(header.version, 0b0011);
+        assert_eq!(header.wide.get(), 0x0100);
+        assert_eq!(header.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(BitBag::<Wide>::SIZE_BITS, 16);
+    }
+}
@@ -0,0 +1,205 @@
+//! [diesel](https://docs.rs/diesel) support, enabled by the `diesel` feature.
+//!
+//! A [`BitBag`] can be used with the `TinyInt`, `SmallInt`, `Integer` and `BigInt` SQL types,
+//! for whichever of them the inner primitive supports on the backend.
+//! Loading permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+//! Use [`Strict`] to reject them instead.
+
+use crate::{BitBag, BitBaggable};
+use ::diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql, Queryable},
+    expression::AsExpression,
+    serialize::{self, Output, ToSql},
+    sql_types::{BigInt, Integer, Nullable, SingleValue, SmallInt, TinyInt},
+};
+use std::fmt::{self, Binary, Debug};
+
+/// Stored like a [`BitBag`], but loading fails if there are bits set which aren't defined in the enum.
+pub struct Strict<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+impl<PossibleFlagsT: BitBaggable> Debug for Strict<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Strict").field(&self.0).finish()
+    }
+}
+
+macro_rules! impl_sql_type {
+    ($($sql_type:ty),* $(,)?) => {$(
+        impl_sql_type!(@expression $sql_type);
+        impl_sql_type!(@expression Nullable<$sql_type>);
+
+        impl<PossibleFlagsT: BitBaggable, DB: Backend> ToSql<$sql_type, DB> for BitBag<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: ToSql<$sql_type, DB>,
+        {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                self.repr.to_sql(out)
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable, DB: Backend> ToSql<$sql_type, DB> for Strict<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: ToSql<$sql_type, DB>,
+        {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                self.0.repr.to_sql(out)
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable, DB: Backend> FromSql<$sql_type, DB> for BitBag<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: FromSql<$sql_type, DB>,
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+                PossibleFlagsT::ReprT::from_sql(bytes).map(BitBag::new)
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable, DB: Backend> FromSql<$sql_type, DB> for Strict<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: FromSql<$sql_type, DB> + Binary + Debug + Send + Sync,
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+                let repr = PossibleFlagsT::ReprT::from_sql(bytes)?;
+                Ok(Strict(BitBag::new_strict(repr)?))
+            }
+        }
+    )*};
+    (@expression $sql_type:ty) => {
+        impl<PossibleFlagsT: BitBaggable> AsExpression<$sql_type> for BitBag<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: AsExpression<$sql_type>,
+        {
+            type Expression = <PossibleFlagsT::ReprT as AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                self.repr.as_expression()
+            }
+        }
+
+        impl<'a, PossibleFlagsT: BitBaggable> AsExpression<$sql_type> for &'a BitBag<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: AsExpression<$sql_type>,
+        {
+            type Expression = <PossibleFlagsT::ReprT as AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                self.repr.as_expression()
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable> AsExpression<$sql_type> for Strict<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: AsExpression<$sql_type>,
+        {
+            type Expression = <PossibleFlagsT::ReprT as AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                self.0.repr.as_expression()
+            }
+        }
+
+        impl<'a, PossibleFlagsT: BitBaggable> AsExpression<$sql_type> for &'a Strict<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: AsExpression<$sql_type>,
+        {
+            type Expression = <PossibleFlagsT::ReprT as AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                self.0.repr.as_expression()
+            }
+        }
+    };
+}
+
+impl_sql_type!(TinyInt, SmallInt, Integer, BigInt);
+
+impl<PossibleFlagsT: BitBaggable, ST: SingleValue, DB: Backend> Queryable<ST, DB>
+    for BitBag<PossibleFlagsT>
+where
+    Self: FromSql<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable, ST: SingleValue, DB: Backend> Queryable<ST, DB>
+    for Strict<PossibleFlagsT>
+where
+    Self: FromSql<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Strict;
+    use crate as bitbag;
+    use crate::{BitBag, BitBaggable};
+    use ::diesel::{prelude::*, sqlite::SqliteConnection};
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(i32)]
+    enum Permission {
+        Read = 0b001,
+        Write = 0b010,
+    }
+
+    ::diesel::table! {
+        users (id) {
+            id -> Integer,
+            permissions -> Integer,
+        }
+    }
+
+    #[test]
+    fn sqlite() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        ::diesel::sql_query(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, permissions INTEGER NOT NULL)",
+        )
+        .execute(&mut conn)
+        .unwrap();
+        let bag = *BitBag::<Permission>::empty()
+            .set(Permission::Read)
+            .set(Permission::Write);
+        ::diesel::insert_into(users::table)
+            .values((users::id.eq(1), users::permissions.eq(bag)))
+            .execute(&mut conn)
+            .unwrap();
+        ::diesel::insert_into(users::table)
+            .values((users::id.eq(2), users::permissions.eq(0b100)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let loaded = users::table
+            .select(users::permissions)
+            .order(users::id)
+            .load::<BitBag<Permission>>(&mut conn)
+            .unwrap();
+        assert_eq!(loaded, [bag, BitBag::new(0b100)]);
+
+        let strict = users::table
+            .select(users::permissions)
+            .filter(users::id.eq(1))
+            .first::<Strict<Permission>>(&mut conn)
+            .unwrap();
+        assert_eq!(strict.0, bag);
+        assert!(users::table
+            .select(users::permissions)
+            .filter(users::id.eq(2))
+            .first::<Strict<Permission>>(&mut conn)
+            .is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Bags of flags which are only known at runtime, see [`DynBitBag`].
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+};
+
+/// A set of flag names, each assigned the next bit index as it is registered.
+///
+/// ```
+/// use bitbag::FlagUniverse;
+///
+/// let mut universe = FlagUniverse::new();
+/// universe.register("read").unwrap();
+/// universe.register("write").unwrap();
+///
+/// let mut bag = universe.bag();
+/// bag.set("write").unwrap();
+/// assert!(bag.is_set("write"));
+/// assert!(!bag.is_set("read"));
+/// assert_eq!(bag.to_string(), "write");
+/// assert!(bag.set("execute").is_err());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FlagUniverse {
+    names: Vec<String>,
+    bits: HashMap<String, usize>,
+}
+
+impl FlagUniverse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a flag, returning its bit index.
+    ///
+    /// Fails if a flag with that name is already registered.
+    pub fn register(&mut self, name: impl Into<String>) -> Result<usize, DuplicateFlag> {
+        let name = name.into();
+        if self.bits.contains_key(&name) {
+            return Err(DuplicateFlag(name));
+        }
+        let bit = self.names.len();
+        self.bits.insert(name.clone(), bit);
+        self.names.push(name);
+        Ok(bit)
+    }
+
+    /// The bit index of `name`, if it is registered
+    pub fn bit(&self, name: &str) -> Option<usize> {
+        self.bits.get(name).copied()
+    }
+
+    /// The name registered at `bit`
+    pub fn name(&self, bit: usize) -> Option<&str> {
+        self.names.get(bit).map(String::as_str)
+    }
+
+    /// The number of registered flags
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The registered names, in bit order
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// New bag with no flags set
+    pub fn bag(&self) -> DynBitBag<'_> {
+        DynBitBag {
+            universe: self,
+            words: Vec::new(),
+        }
+    }
+
+    /// Parse `A | B` formatted flag names, as written by [`DynBitBag`]'s [`Display`] implementation.
+    ///
+    /// `<unset>` and the empty string parse to an empty bag.
+    pub fn parse(&self, s: &str) -> Result<DynBitBag<'_>, UnknownFlag> {
+        let mut bag = self.bag();
+        let s = s.trim();
+        if s.is_empty() || s == "<unset>" {
+            return Ok(bag);
+        }
+        for name in s.split('|') {
+            bag.set(name.trim())?;
+        }
+        Ok(bag)
+    }
+}
+
+/// Like a [`BitBag`](crate::BitBag), but with flags defined at runtime by a [`FlagUniverse`].
+///
+/// Bags from different universes are never equal.
+#[derive(Clone)]
+pub struct DynBitBag<'universe> {
+    universe: &'universe FlagUniverse,
+    words: Vec<u64>,
+}
+
+impl<'universe> DynBitBag<'universe> {
+    /// The universe this bag's flags come from
+    pub fn universe(&self) -> &'universe FlagUniverse {
+        self.universe
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Unregistered names are never set
+    pub fn is_set(&self, name: &str) -> bool {
+        self.universe
+            .bit(name)
+            .is_some_and(|bit| self.is_set_bit(bit))
+    }
+
+    pub fn is_set_bit(&self, bit: usize) -> bool {
+        let (word, bit) = locate(bit);
+        self.words.get(word).is_some_and(|word| word & bit != 0)
+    }
+
+    /// Fails if `name` isn't registered in the universe
+    pub fn set(&mut self, name: &str) -> Result<&mut Self, UnknownFlag> {
+        let bit = self.lookup(name)?;
+        let (word, bit) = locate(bit);
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= bit;
+        Ok(self)
+    }
+
+    /// Fails if `name` isn't registered in the universe
+    pub fn unset(&mut self, name: &str) -> Result<&mut Self, UnknownFlag> {
+        let bit = self.lookup(name)?;
+        let (word, bit) = locate(bit);
+        if let Some(word) = self.words.get_mut(word) {
+            *word &= !bit;
+        }
+        Ok(self)
+    }
+
+    pub fn clear_all(&mut self) -> &mut Self {
+        self.words.clear();
+        self
+    }
+
+    /// The names of the set flags, in bit order
+    pub fn iter(&self) -> impl Iterator<Item = &'universe str> + '_ {
+        let universe = self.universe;
+        (0..universe.len())
+            .filter(move |bit| self.is_set_bit(*bit))
+            .filter_map(move |bit| universe.name(bit))
+    }
+
+    fn lookup(&self, name: &str) -> Result<usize, UnknownFlag> {
+        self.universe
+            .bit(name)
+            .ok_or_else(|| UnknownFlag(name.to_string()))
+    }
+
+    /// The words without trailing zeroes, so equal bags compare equal however they were built
+    fn significant_words(&self) -> &[u64] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |ix| ix + 1);
+        &self.words[..len]
+    }
+}
+
+fn locate(bit: usize) -> (usize, u64) {
+    (bit / 64, 1 << (bit % 64))
+}
+
+impl PartialEq for DynBitBag<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.universe, other.universe)
+            && self.significant_words() == other.significant_words()
+    }
+}
+
+impl Eq for DynBitBag<'_> {}
+
+impl Hash for DynBitBag<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_words().hash(state);
+    }
+}
+
+impl Display for DynBitBag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.iter();
+        match names.next() {
+            None => f.write_str("<unset>"),
+            Some(first) => {
+                f.write_str(first)?;
+                for name in names {
+                    write!(f, " | {name}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Debug for DynBitBag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// The error returned when registering a name in a [`FlagUniverse`] twice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateFlag(pub String);
+
+impl std::error::Error for DuplicateFlag {}
+
+impl Display for DuplicateFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the flag {:?} is already registered", self.0)
+    }
+}
+
+/// The error returned when a name isn't registered in a [`FlagUniverse`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownFlag(pub String);
+
+impl std::error::Error for UnknownFlag {}
+
+impl Display for UnknownFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the flag {:?} is not registered", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn universe() -> FlagUniverse {
+        let mut universe = FlagUniverse::new();
+        for ix in 0..100 {
+            universe.register(format!("f{ix}")).unwrap();
+        }
+        universe
+    }
+
+    #[test]
+    fn register() {
+        let mut universe = universe();
+        assert_eq!(universe.register("extra"), Ok(100));
+        assert_eq!(universe.register("f3"), Err(DuplicateFlag("f3".into())));
+        assert_eq!(universe.bit("f3"), Some(3));
+        assert_eq!(universe.name(100), Some("extra"));
+        assert_eq!(universe.len(), 101);
+    }
+
+    #[test]
+    fn flags() {
+        let universe = universe();
+        let mut bag = universe.bag();
+        assert!(bag.is_empty());
+        assert_eq!(bag.to_string(), "<unset>");
+        bag.set("f99").unwrap().set("f1").unwrap();
+        assert!(bag.is_set("f99"));
+        assert!(!bag.is_set("f2"));
+        assert!(!bag.is_set("nope"));
+        assert_eq!(bag.iter().collect::<Vec<_>>(), ["f1", "f99"]);
+        assert_eq!(bag.to_string(), "f1 | f99");
+        assert_eq!(format!("{bag:?}"), r#"{"f1", "f99"}"#);
+        assert_eq!(bag.set("nope").unwrap_err(), UnknownFlag("nope".into()));
+
+        bag.unset("f99").unwrap();
+        let mut other = universe.bag();
+        other.set("f1").unwrap();
+        assert_eq!(bag, other);
+        assert_ne!(bag, universe.bag());
+    }
+
+    #[test]
+    fn parse() {
+        let universe = universe();
+        let bag = universe.parse("f1 | f70").unwrap();
+        assert_eq!(universe.parse(&bag.to_string()).unwrap(), bag);
+        assert!(universe.parse("<unset>").unwrap().is_empty());
+        assert!(universe.parse("f1 | nope").is_err());
+    }
+}
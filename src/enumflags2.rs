@@ -0,0 +1,110 @@
+//! Conversions to and from [enumflags2](https://docs.rs/enumflags2)'s [`BitFlags`], enabled by the `enumflags2` feature.
+//!
+//! When an enum is both [`BitBaggable`] and `#[bitflags]`, a [`BitFlags`] converts into a bag with [`From`],
+//! and a bag converts back with [`TryFrom`], which fails on unrecognised bits.
+//! [`to_bitflags`] and [`from_bitflags`] convert between different enums with the same bits.
+//!
+//! `#[bitflags]` already implements `|` for the enum, so don't also derive bitbag's `BitOr`.
+//! ```
+//! use bitbag::{BitBag, BitBaggable};
+//! use enumflags2::{bitflags, BitFlags};
+//! use std::convert::TryFrom as _;
+//!
+//! #[bitflags]
+//! #[derive(Debug, Clone, Copy, PartialEq, BitBaggable)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! let flags: BitFlags<Perm> = Perm::Read | Perm::Write;
+//! let bag = BitBag::from(flags);
+//! assert_eq!(bag.get(), 0b0011);
+//! assert_eq!(BitFlags::try_from(bag).unwrap(), flags);
+//! assert!(BitFlags::<Perm>::try_from(BitBag::<Perm>::new(0b0100)).is_err());
+//! ```
+
+use crate::{BitBag, BitBaggable, NonFlagBits};
+use ::enumflags2::{BitFlag, BitFlags, FromBitsError};
+use core::convert::TryFrom;
+
+impl<PossibleFlagsT> From<BitFlags<PossibleFlagsT>> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT: BitBaggable + BitFlag<Numeric = <PossibleFlagsT as BitBaggable>::ReprT>,
+{
+    fn from(flags: BitFlags<PossibleFlagsT>) -> Self {
+        BitBag::new(flags.bits())
+    }
+}
+
+impl<PossibleFlagsT> TryFrom<BitBag<PossibleFlagsT>> for BitFlags<PossibleFlagsT>
+where
+    PossibleFlagsT: BitBaggable + BitFlag<Numeric = <PossibleFlagsT as BitBaggable>::ReprT>,
+{
+    type Error = FromBitsError<PossibleFlagsT>;
+
+    fn try_from(bag: BitBag<PossibleFlagsT>) -> Result<Self, Self::Error> {
+        to_bitflags(bag)
+    }
+}
+
+/// The [`BitFlags`] of `OtherT` with the same bits as `bag`, failing if `OtherT` doesn't declare them all
+pub fn to_bitflags<PossibleFlagsT, OtherT>(
+    bag: BitBag<PossibleFlagsT>,
+) -> Result<BitFlags<OtherT>, FromBitsError<OtherT>>
+where
+    PossibleFlagsT: BitBaggable,
+    OtherT: BitFlag<Numeric = PossibleFlagsT::ReprT>,
+{
+    BitFlags::from_bits(bag.get())
+}
+
+/// The bag with the same bits as `flags`, failing if the enum doesn't declare them all
+pub fn from_bitflags<PossibleFlagsT, OtherT>(
+    flags: BitFlags<OtherT>,
+) -> Result<BitBag<PossibleFlagsT>, NonFlagBits<PossibleFlagsT>>
+where
+    PossibleFlagsT: BitBaggable,
+    OtherT: BitFlag<Numeric = PossibleFlagsT::ReprT>,
+{
+    BitBag::new_strict(flags.bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bitbag;
+    use crate::tests::FooFlags;
+    use ::enumflags2::bitflags;
+
+    #[bitflags]
+    #[derive(Debug, Clone, Copy, PartialEq, BitBaggable)]
+    #[repr(u8)]
+    enum Perm {
+        Read = 0b0001,
+        Write = 0b0010,
+        Exec = 0b1_0000,
+    }
+
+    #[test]
+    fn same_enum() {
+        let flags = Perm::Read | Perm::Exec;
+        let bag = BitBag::from(flags);
+        assert!(bag.is_set(Perm::Exec));
+        assert_eq!(BitFlags::try_from(bag).unwrap(), flags);
+        let e = BitFlags::<Perm>::try_from(BitBag::<Perm>::new(0b0101)).unwrap_err();
+        assert_eq!(e.invalid_bits(), 0b0100);
+    }
+
+    #[test]
+    fn other_enum() {
+        let bag = from_bitflags::<FooFlags, _>(Perm::Read | Perm::Write).unwrap();
+        assert_eq!(bag.get(), 0b0011);
+        assert!(from_bitflags::<FooFlags, _>(BitFlags::from(Perm::Exec)).is_err());
+        assert_eq!(
+            to_bitflags::<_, Perm>(bag).unwrap(),
+            Perm::Read | Perm::Write
+        );
+    }
+}
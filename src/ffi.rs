@@ -0,0 +1,59 @@
+//! Working with the same flags from C, with `#[bitbag(ffi = "prefix")]` on the derive.
+//!
+//! The option generates `#[no_mangle] extern "C"` functions next to the enum, named with the prefix,
+//! which take and return the enum's `#[repr(..)]` primitive, so cbindgen (with `parse.expand`) can write a header for them:
+//! - `prefix_contains(bits, flags) -> bool`: whether every bit of `flags` is set in `bits`.
+//! - `prefix_is_valid(bits) -> bool`: whether every bit set in `bits` is part of a flag.
+//! - `prefix_all() -> repr`: every bit which is part of a flag.
+//! - `prefix_name(flag) -> *const c_char`: the flag's name as a static NUL-terminated string, or null if `flag` isn't exactly one flag.
+//!
+//! [`BitBag`](crate::BitBag) is `#[repr(transparent)]`, so Rust functions in the same library can also take and return bags directly.
+//! ```
+//! use bitbag::BitBaggable;
+//! use std::ffi::CStr;
+//!
+//! #[derive(BitBaggable)]
+//! #[bitbag(ffi = "perm")]
+//! #[repr(u32)]
+//! pub enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! assert!(perm_contains(0b0011, Perm::Write as u32));
+//! assert!(!perm_is_valid(0b0100));
+//! assert_eq!(perm_all(), 0b0011);
+//! let name = unsafe { CStr::from_ptr(perm_name(0b0010)) };
+//! assert_eq!(name.to_str(), Ok("Write"));
+//! assert!(perm_name(0b0011).is_null());
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::BitBaggable;
+    use std::ffi::CStr;
+
+    #[derive(BitBaggable)]
+    #[bitbag(ffi = "bitbag_test_mode")]
+    #[repr(u16)]
+    #[allow(dead_code)]
+    enum Mode {
+        Read = 0b0001,
+        Write = 0b0010,
+        ReadWrite = 0b0011,
+    }
+
+    #[test]
+    fn helpers() {
+        assert!(bitbag_test_mode_contains(0b0011, 0b0001));
+        assert!(!bitbag_test_mode_contains(0b0010, 0b0011));
+        assert!(bitbag_test_mode_is_valid(0b0011));
+        assert!(!bitbag_test_mode_is_valid(0b1000));
+        assert_eq!(bitbag_test_mode_all(), 0b0011);
+        // SAFETY: non-null names are static NUL-terminated strings
+        let name = unsafe { CStr::from_ptr(bitbag_test_mode_name(0b0011)) };
+        assert_eq!(name.to_str(), Ok("ReadWrite"));
+        assert!(bitbag_test_mode_name(0b0100).is_null());
+    }
+}
@@ -0,0 +1,87 @@
+//! Conversions to and from [flagset](https://docs.rs/flagset) `FlagSet`s, enabled by the `flagset` feature.
+//!
+//! flagset defines its own enums with `flags!`, so these convert between a bag and a [`FlagSet`] of a different enum with the same bits.
+//! Both directions are checked, since either enum may declare bits the other doesn't.
+//! Neither type is local to your crate, so the conversions are functions, rather than [`From`] implementations.
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use flagset::{flags, FlagSet};
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! flags! {
+//!     enum Access: u8 {
+//!         Read = 0b0001,
+//!         Write = 0b0010,
+//!         Exec = 0b0100,
+//!     }
+//! }
+//!
+//! let set: FlagSet<Access> = bitbag::flagset::to_flagset(Perm::Read | Perm::Write).unwrap();
+//! assert_eq!(set, Access::Read | Access::Write);
+//!
+//! let bag: BitBag<Perm> = bitbag::flagset::from_flagset(set).unwrap();
+//! assert_eq!(bag, Perm::Read | Perm::Write);
+//! assert!(bitbag::flagset::from_flagset::<Perm, _>(Access::Exec.into()).is_err());
+//! ```
+
+use crate::{BitBag, BitBaggable, NonFlagBits};
+use ::flagset::{FlagSet, Flags, InvalidBits};
+
+/// The [`FlagSet`] with the same bits as `bag`, failing if `FlagsT` doesn't declare them all
+pub fn to_flagset<PossibleFlagsT, FlagsT>(
+    bag: BitBag<PossibleFlagsT>,
+) -> Result<FlagSet<FlagsT>, InvalidBits>
+where
+    PossibleFlagsT: BitBaggable,
+    FlagsT: Flags<Type = PossibleFlagsT::ReprT>,
+{
+    FlagSet::new(bag.get())
+}
+
+/// The bag with the same bits as `set`, failing if the enum doesn't declare them all
+pub fn from_flagset<PossibleFlagsT, FlagsT>(
+    set: FlagSet<FlagsT>,
+) -> Result<BitBag<PossibleFlagsT>, NonFlagBits<PossibleFlagsT>>
+where
+    PossibleFlagsT: BitBaggable,
+    FlagsT: Flags<Type = PossibleFlagsT::ReprT>,
+{
+    BitBag::new_strict(set.bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+    use ::flagset::flags;
+
+    flags! {
+        enum Foos: u8 {
+            A,
+            B,
+            C,
+            D,
+            E,
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let set = to_flagset::<_, Foos>(A | D).unwrap();
+        assert_eq!(set, Foos::A | Foos::D);
+        assert_eq!(from_flagset::<FooFlags, _>(set).unwrap(), A | D);
+    }
+
+    #[test]
+    fn unrecognised() {
+        let e = from_flagset::<FooFlags, _>(Foos::A | Foos::E).unwrap_err();
+        assert_eq!(e.unrecognised(), 0b1_0000);
+        assert!(to_flagset::<_, Foos>(BitBag::<FooFlags>::new(0b10_0000)).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Formatting adapters for [`BitBag`].
+
+use crate::{BitBag, BitBaggable, Order};
+use core::{
+    any::type_name,
+    fmt::{self, Binary, LowerHex, UpperHex},
+};
+use num::{PrimInt as _, Zero as _};
+
+/// Forwards to the inner primitive, so the usual flags like `{:#010b}` apply.
+impl<PossibleFlagsT: BitBaggable> Binary for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Binary::fmt(&self.repr, f)
+    }
+}
+
+/// Forwards to the inner primitive.
+impl<PossibleFlagsT: BitBaggable> LowerHex for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: LowerHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        LowerHex::fmt(&self.repr, f)
+    }
+}
+
+/// Forwards to the inner primitive.
+impl<PossibleFlagsT: BitBaggable> UpperHex for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: UpperHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        UpperHex::fmt(&self.repr, f)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// The inner primitive as lowercase hex, with a leading `0x`, as accepted by [`BitBag::from_hex_str`]
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_string(&self) -> alloc::string::String
+    where
+        PossibleFlagsT::ReprT: LowerHex,
+    {
+        alloc::format!("{:#x}", self.repr)
+    }
+
+    /// Format as binary, zero-padded to the width of the repr, e.g `0b00001010` for a `u8`.
+    ///
+    /// This keeps successive register dumps aligned column-wise.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// # }
+    /// let bag = BitBag::<Flags>::new(0b1010);
+    /// assert_eq!(bag.padded_binary().to_string(), "0b00001010");
+    /// ```
+    pub fn padded_binary(&self) -> PaddedBinary<'_, PossibleFlagsT> {
+        PaddedBinary { bag: self }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// A multi-line table of every declared flag, with its bits, and whether it is set.
+    ///
+    /// Unrecognised bits get a row of their own.
+    /// Intended for support bundles, and `--debug` output.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// #     Long = 0b0100,
+    /// # }
+    /// let bag = BitBag::<Flags>::new(0b1001);
+    /// println!("{}", bag.dump());
+    /// // my_crate::Flags = 0b00001001
+    /// //   [x] A                    0b00000001
+    /// //   [ ] B                    0b00000010
+    /// //   [ ] Long                 0b00000100
+    /// //   [x] <unrecognised bits>  0b00001000
+    /// ```
+    pub fn dump(&self) -> Dump<'_, PossibleFlagsT> {
+        Dump { bag: self }
+    }
+}
+
+/// Returned by [`BitBag::dump`]
+pub struct Dump<'a, PossibleFlagsT: BitBaggable> {
+    bag: &'a BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Display for Dump<'_, PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNRECOGNISED: &str = "<unrecognised bits>";
+        let unrecognised = self.bag.unrecognised_bits();
+        let width = PossibleFlagsT::VARIANTS
+            .iter()
+            .map(|(name, _, _)| name.len())
+            .chain(unrecognised.map(|_| UNRECOGNISED.len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "{} = {}",
+            type_name::<PossibleFlagsT>(),
+            self.bag.padded_binary()
+        )?;
+        for (name, _, repr) in PossibleFlagsT::VARIANTS {
+            let mark = match self.bag.is_set_raw(*repr) {
+                true => 'x',
+                false => ' ',
+            };
+            writeln!(
+                f,
+                "  [{mark}] {name:width$}  {}",
+                BitBag::<PossibleFlagsT>::new(*repr).padded_binary()
+            )?;
+        }
+        if let Some(unrecognised) = unrecognised {
+            writeln!(
+                f,
+                "  [x] {UNRECOGNISED:width$}  {}",
+                BitBag::<PossibleFlagsT>::new(unrecognised).padded_binary()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Like [`Display`](fmt::Display), but listing flags in the given order, rather than [`BitBaggable::ORDER`]
+    pub fn display_in(&self, order: Order) -> DisplayIn<'_, PossibleFlagsT> {
+        DisplayIn { bag: self, order }
+    }
+}
+
+/// Returned by [`BitBag::display_in`]
+pub struct DisplayIn<'a, PossibleFlagsT: BitBaggable> {
+    bag: &'a BitBag<PossibleFlagsT>,
+    order: Order,
+}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Display for DisplayIn<'_, PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bag
+            .write_names_with(f, self.order, |(name, _, _)| name)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// The set flags' names, written like [`Display`](fmt::Display) only when the adapter is formatted.
+    ///
+    /// The adapter holds a copy of the bag rather than a borrow, so it can be passed to logging macros,
+    /// or kept in a structured field, and a line which is filtered out never walks the flags.
+    /// Its [`Debug`](fmt::Debug) implementation writes the same names, for `?field` style captures.
+    /// ```
+    /// # use bitbag::{BitBaggable, BitOr};
+    /// # #[derive(BitBaggable, BitOr)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let names = (Flags::A | Flags::B).lazy_names();
+    /// assert_eq!(format!("{names} {names:?}"), "A | B A | B");
+    /// ```
+    pub fn lazy_names(&self) -> LazyNames<PossibleFlagsT> {
+        LazyNames { bag: *self }
+    }
+}
+
+/// Returned by [`BitBag::lazy_names`]
+pub struct LazyNames<PossibleFlagsT: BitBaggable> {
+    bag: BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for LazyNames<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for LazyNames<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Display for LazyNames<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.bag, f)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Debug for LazyNames<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.bag, f)
+    }
+}
+
+/// Returned by [`BitBag::padded_binary`]
+pub struct PaddedBinary<'a, PossibleFlagsT: BitBaggable> {
+    bag: &'a BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Display for PaddedBinary<'_, PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = PossibleFlagsT::ReprT::zero().count_zeros() as usize;
+        write!(f, "{:#0width$b}", self.bag.repr, width = bits + "0b".len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::tests::FooFlags::{self, *};
+    use crate::{BitBag, BitBaggable};
+
+    #[derive(BitBaggable)]
+    #[repr(i32)]
+    enum Wide {
+        Sign = i32::MIN,
+    }
+
+    #[test]
+    fn binary() {
+        let bag = A | C;
+        assert_eq!(format!("{bag:b}"), "101");
+        assert_eq!(format!("{bag:#06b}"), "0b0101");
+    }
+
+    #[test]
+    fn hex() {
+        let bag = B | C | D;
+        assert_eq!(bag.to_hex_string(), "0xe");
+        assert_eq!(format!("{bag:#04X}"), "0x0E");
+        assert_eq!(BitBag::<FooFlags>::empty().to_hex_string(), "0x0");
+    }
+
+    #[test]
+    fn dump() {
+        let expected = format!(
+            "{} = 0b00000110\n  [ ] A  0b00000001\n  [x] B  0b00000010\n  [x] C  0b00000100\n  [ ] D  0b00001000\n",
+            std::any::type_name::<FooFlags>()
+        );
+        assert_eq!((B | C).dump().to_string(), expected);
+
+        let dump = BitBag::<FooFlags>::new(0b1000_0001).dump().to_string();
+        assert!(dump.contains("\n  [x] A                    0b00000001\n"));
+        assert!(dump.ends_with("\n  [x] <unrecognised bits>  0b10000000\n"));
+    }
+
+    #[test]
+    fn lazy_names() {
+        let names = (A | C).lazy_names();
+        assert_eq!(names.to_string(), "A | C");
+        assert_eq!(format!("{names:?}"), "A | C");
+        assert_eq!(
+            BitBag::<FooFlags>::empty().lazy_names().to_string(),
+            BitBag::<FooFlags>::empty().to_string()
+        );
+    }
+
+    #[test]
+    fn padded_binary() {
+        assert_eq!((A | D).padded_binary().to_string(), "0b00001001");
+        assert_eq!(
+            BitBag::<FooFlags>::empty().padded_binary().to_string(),
+            "0b00000000"
+        );
+        assert_eq!(
+            BitBag::<Wide>::all().padded_binary().to_string(),
+            format!("0b1{}", "0".repeat(31))
+        );
+    }
+}
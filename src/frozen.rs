@@ -0,0 +1,137 @@
+//! A bag which can no longer be modified, see [`FrozenBitBag`].
+
+use crate::{BitBag, BitBaggable};
+use core::{
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A [`BitBag`] which has been finalised with [`BitBag::freeze`].
+///
+/// It derefs to the bag, so all of the non-mutating methods are available, but never hands out a `&mut`.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, BitOr, FrozenBitBag};
+/// # #[derive(BitBaggable, BitOr)]
+/// # #[repr(u8)]
+/// # enum Perm {
+/// #     Read = 0b01,
+/// #     Write = 0b10,
+/// # }
+/// let mut perms = BitBag::just(Perm::Read);
+/// perms.set(Perm::Write);
+/// let perms: FrozenBitBag<Perm> = perms.freeze();
+///
+/// assert!(perms.is_set(Perm::Write));
+/// assert_eq!(perms.to_string(), "Read | Write");
+/// ```
+/// ```compile_fail
+/// # use bitbag::{BitBag, BitBaggable};
+/// # #[derive(BitBaggable)]
+/// # #[repr(u8)]
+/// # enum Perm {
+/// #     Read = 0b01,
+/// # }
+/// let mut perms = BitBag::<Perm>::empty().freeze();
+/// perms.set(Perm::Read);
+/// ```
+pub struct FrozenBitBag<PossibleFlagsT: BitBaggable> {
+    bag: BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Stop the bag being modified, see [`FrozenBitBag`]
+    pub fn freeze(self) -> FrozenBitBag<PossibleFlagsT> {
+        FrozenBitBag { bag: self }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> FrozenBitBag<PossibleFlagsT> {
+    /// A modifiable copy of the bag
+    pub fn thaw(&self) -> BitBag<PossibleFlagsT> {
+        self.bag
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Deref for FrozenBitBag<PossibleFlagsT> {
+    type Target = BitBag<PossibleFlagsT>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bag
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>> for FrozenBitBag<PossibleFlagsT> {
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        bag.freeze()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for FrozenBitBag<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for FrozenBitBag<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> PartialEq for FrozenBitBag<PossibleFlagsT> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bag == other.bag
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Eq for FrozenBitBag<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> PartialEq<BitBag<PossibleFlagsT>>
+    for FrozenBitBag<PossibleFlagsT>
+{
+    fn eq(&self, other: &BitBag<PossibleFlagsT>) -> bool {
+        self.bag == *other
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Hash for FrozenBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bag.hash(state);
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for FrozenBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FrozenBitBag").field(&self.bag).finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for FrozenBitBag<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.bag, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::*;
+
+    #[test]
+    fn read_only() {
+        let frozen = (A | C).freeze();
+        let shared = frozen;
+        assert_eq!(shared, A | C);
+        assert!(frozen.contains_all(BitBag::just(C)));
+        assert_eq!(frozen.iter_names().collect::<Vec<_>>(), ["A", "C"]);
+        assert_eq!(format!("{frozen:?}"), "FrozenBitBag(BitBag { repr: 5 })");
+
+        let mut thawed = frozen.thaw();
+        thawed.set(B);
+        assert_eq!(frozen, A | C);
+        assert_eq!(FrozenBitBag::from(thawed), (A | B | C).freeze());
+    }
+}
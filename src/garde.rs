@@ -0,0 +1,39 @@
+//! garde support, enabled by the `garde` feature.
+//!
+//! [`MissingFlags`] and [`ForbiddenFlags`] convert to a [`garde::Error`](::garde::Error) with the same message, so
+//! policy checks can be `?`ed in a `#[garde(custom(...))]` function.
+
+use crate::{BitBaggable, ForbiddenFlags, MissingFlags};
+
+impl<PossibleFlagsT: BitBaggable> From<MissingFlags<PossibleFlagsT>> for ::garde::Error {
+    fn from(e: MissingFlags<PossibleFlagsT>) -> Self {
+        ::garde::Error::new(e.to_string())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<ForbiddenFlags<PossibleFlagsT>> for ::garde::Error {
+    fn from(e: ForbiddenFlags<PossibleFlagsT>) -> Self {
+        ::garde::Error::new(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+
+    fn policy(bag: &BitBag<FooFlags>, _: &()) -> ::garde::Result {
+        bag.require_all(BitBag::just(A))?;
+        bag.forbid(BitBag::just(D))?;
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        assert!(policy(&(A | B), &()).is_ok());
+        let e = policy(&BitBag::just(B), &()).unwrap_err();
+        assert_eq!(e.message(), "missing required flags: A");
+        let e = policy(&(A | D), &()).unwrap_err();
+        assert_eq!(e.message(), "forbidden flags are set: D");
+    }
+}
@@ -2,11 +2,12 @@
 //! So manually implement here.
 
 use crate::{BitBag, BitBaggable};
-use num::Zero as _;
-use std::{
+use core::{
+    cmp::Ordering,
     fmt::{self, Debug},
     hash::Hash,
 };
+use num::Zero as _;
 
 impl<PossibleFlagsT: BitBaggable> PartialEq for BitBag<PossibleFlagsT> {
     fn eq(&self, other: &Self) -> bool {
@@ -16,11 +17,26 @@ impl<PossibleFlagsT: BitBaggable> PartialEq for BitBag<PossibleFlagsT> {
 
 impl<PossibleFlagsT: BitBaggable> Eq for BitBag<PossibleFlagsT> {}
 
+/// A total order over the inner primitive, so bags can key a `BTreeMap` or be sorted.
+///
+/// This is not the subset order, see [`BitBag::contains_all`] for that.
+impl<PossibleFlagsT: BitBaggable> Ord for BitBag<PossibleFlagsT> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.repr.cmp(&other.repr)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> PartialOrd for BitBag<PossibleFlagsT> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<PossibleFlagsT: BitBaggable> Hash for BitBag<PossibleFlagsT>
 where
     PossibleFlagsT::ReprT: Hash,
 {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.repr.hash(state);
     }
 }
@@ -36,12 +52,13 @@ where
 
 impl<PossibleFlagsT: BitBaggable> Clone for BitBag<PossibleFlagsT> {
     fn clone(&self) -> Self {
-        Self { repr: self.repr }
+        *self
     }
 }
 
 impl<PossibleFlagsT: BitBaggable> Copy for BitBag<PossibleFlagsT> {}
 
+/// The empty bag, as [`BitBag::empty`]
 impl<PossibleFlagsT: BitBaggable> Default for BitBag<PossibleFlagsT> {
     fn default() -> Self {
         Self {
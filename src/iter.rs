@@ -1,4 +1,18 @@
 use crate::{BitBag, BitBaggable};
+use core::convert::TryFrom as _;
+use num::{One as _, PrimInt as _, Zero as _};
+
+/// The order in which flags are iterated over, and displayed.
+///
+/// The default for an enum is [`BitBaggable::ORDER`], which can be changed with `#[bitbag(order = "ascending")]` on the derive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+    /// The order the variants are declared in the enum
+    #[default]
+    Declaration,
+    /// By the lowest bit set in each variant, falling back to declaration order for ties
+    Ascending,
+}
 
 impl<PossibleFlagsT: BitBaggable> IntoIterator for BitBag<PossibleFlagsT>
 where
@@ -9,17 +23,76 @@ where
     type IntoIter = BitBagIterator<PossibleFlagsT>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Self::IntoIter {
-            bag: self,
-            variant_iterator: PossibleFlagsT::VARIANTS.iter(),
+        self.iter_in(PossibleFlagsT::ORDER)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Iterate over the set flags in the given order, rather than [`BitBaggable::ORDER`]
+    pub fn iter_in(self, order: Order) -> BitBagIterator<PossibleFlagsT>
+    where
+        PossibleFlagsT: Clone,
+    {
+        BitBagIterator {
+            set: set_variants(self, order),
+        }
+    }
+
+    /// Iterate over the names of the set flags, in [`BitBaggable::ORDER`], leaving out any unrecognised bits.
+    ///
+    /// This doesn't allocate.
+    pub fn iter_names(self) -> BitBagNames<PossibleFlagsT> {
+        BitBagNames {
+            set: set_variants(self, PossibleFlagsT::ORDER),
         }
     }
+
+    /// Translate each set flag into a flag of another enum, leaving out flags which map to [`None`], and any unrecognised bits.
+    ///
+    /// Matching on the flag in `f` makes the compiler check that every variant is translated.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable, BitOr};
+    /// #[derive(Clone, BitBaggable, BitOr)]
+    /// #[repr(u8)]
+    /// enum Capability {
+    ///     Read = 0b001,
+    ///     Write = 0b010,
+    ///     Audit = 0b100,
+    /// }
+    ///
+    /// #[derive(BitBaggable)]
+    /// #[repr(u32)]
+    /// enum ApiScope {
+    ///     Get = 0x10,
+    ///     Put = 0x20,
+    /// }
+    ///
+    /// let scopes = (Capability::Read | Capability::Audit).map_flags(|cap| match cap {
+    ///     Capability::Read => Some(ApiScope::Get),
+    ///     Capability::Write => Some(ApiScope::Put),
+    ///     Capability::Audit => None,
+    /// });
+    /// assert_eq!(scopes.get(), 0x10);
+    /// ```
+    pub fn map_flags<OtherT: BitBaggable>(
+        self,
+        mut f: impl FnMut(PossibleFlagsT) -> Option<OtherT>,
+    ) -> BitBag<OtherT>
+    where
+        PossibleFlagsT: Clone,
+    {
+        let mut mapped = BitBag::empty();
+        for flag in self {
+            if let Some(other) = f(flag) {
+                mapped.set(other);
+            }
+        }
+        mapped
+    }
 }
 
 pub struct BitBagIterator<PossibleFlagsT: BitBaggable> {
-    bag: BitBag<PossibleFlagsT>,
-    variant_iterator:
-        std::slice::Iter<'static, (&'static str, PossibleFlagsT, PossibleFlagsT::ReprT)>,
+    set: SetVariants<PossibleFlagsT>,
 }
 
 impl<PossibleFlagsT: BitBaggable> Iterator for BitBagIterator<PossibleFlagsT>
@@ -29,11 +102,378 @@ where
     type Item = PossibleFlagsT;
 
     fn next(&mut self) -> Option<Self::Item> {
-        for (_, value, _) in self.variant_iterator.by_ref() {
-            if self.bag.is_set(value.clone()) {
-                return Some(value.clone());
+        self.set.next().map(|(_, value, _)| value.clone())
+    }
+}
+
+/// The names of the flags set in a bag, see [`BitBag::iter_names`]
+pub struct BitBagNames<PossibleFlagsT: BitBaggable> {
+    set: SetVariants<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Iterator for BitBagNames<PossibleFlagsT> {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.set.next().map(|(name, _, _)| *name)
+    }
+}
+
+/// The variants set in `bag`, in the given order
+pub(crate) fn set_variants<PossibleFlagsT: BitBaggable>(
+    bag: BitBag<PossibleFlagsT>,
+    order: Order,
+) -> SetVariants<PossibleFlagsT> {
+    SetVariants {
+        bag,
+        variants: variants_in(order),
+        rest: match PossibleFlagsT::SINGLE_BITS {
+            true => Some(bag.repr),
+            false => None,
+        },
+    }
+}
+
+pub(crate) struct SetVariants<PossibleFlagsT: BitBaggable> {
+    bag: BitBag<PossibleFlagsT>,
+    variants: Variants<PossibleFlagsT>,
+    /// For [`BitBaggable::SINGLE_BITS`] enums, the bits not yet walked.
+    ///
+    /// Both orders are then lowest bit first, so we only visit the set bits, rather than every variant.
+    rest: Option<PossibleFlagsT::ReprT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Iterator for SetVariants<PossibleFlagsT> {
+    type Item = &'static (&'static str, PossibleFlagsT, PossibleFlagsT::ReprT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(rest) = &mut self.rest {
+            while !rest.is_zero() {
+                let bit = rest.trailing_zeros();
+                *rest = *rest & !PossibleFlagsT::ReprT::one().unsigned_shl(bit);
+                let variant = PossibleFlagsT::variant_at_bit(bit)
+                    .and_then(|ix| PossibleFlagsT::VARIANTS.get(ix));
+                if variant.is_some() {
+                    return variant;
+                }
+            }
+            return None;
+        }
+        let bag = self.bag;
+        self.variants
+            .by_ref()
+            .find(|(_, _, repr)| bag.is_set_raw(*repr))
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Iterate over every combination of the declared flags, starting with the empty bag.
+    ///
+    /// There are `2^n` for `n` variants, so this is for small enums, like in exhaustive tests.
+    /// Overlapping variants can produce the same bag more than once.
+    ///
+    /// # Panics
+    /// If the enum has more than 128 variants.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let subsets = BitBag::<Flags>::all_subsets().map(|bag| bag.get()).collect::<Vec<_>>();
+    /// assert_eq!(subsets, [0b00, 0b01, 0b10, 0b11]);
+    /// ```
+    pub fn all_subsets() -> Subsets<PossibleFlagsT> {
+        let len = PossibleFlagsT::VARIANTS.len();
+        assert!(len <= 128, "all_subsets supports at most 128 variants");
+        Subsets {
+            next: Some(0),
+            last: u128::MAX.checked_shr(128 - len as u32).unwrap_or(0),
+            _flags: core::marker::PhantomData,
+        }
+    }
+
+    /// Set each declared flag for which `set` returns `true`, asking once per variant in declaration order.
+    ///
+    /// Random generators pass a coin flip, so every combination of flags is equally likely.
+    #[cfg(any(
+        feature = "arbitrary",
+        feature = "proptest",
+        feature = "quickcheck",
+        feature = "rand"
+    ))]
+    pub(crate) fn from_bools(mut set: impl FnMut() -> bool) -> Self {
+        let mut bag = Self::empty();
+        for (_, _, repr) in PossibleFlagsT::VARIANTS {
+            if set() {
+                bag.set_raw(*repr);
             }
         }
-        None
+        bag
+    }
+}
+
+/// Every combination of an enum's flags, see [`BitBag::all_subsets`]
+pub struct Subsets<PossibleFlagsT: BitBaggable> {
+    /// Bit `n` selects the `n`th variant, [`None`] once `last` has been yielded
+    next: Option<u128>,
+    /// Every variant selected
+    last: u128,
+    _flags: core::marker::PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Iterator for Subsets<PossibleFlagsT> {
+    type Item = BitBag<PossibleFlagsT>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let selected = self.next?;
+        let mut bag = BitBag::empty();
+        for (ix, (_, _, repr)) in PossibleFlagsT::VARIANTS.iter().enumerate() {
+            if selected >> ix & 1 != 0 {
+                bag.set_raw(*repr);
+            }
+        }
+        self.next = match selected == self.last {
+            true => None,
+            false => Some(selected + 1),
+        };
+        Some(bag)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.next {
+            Some(next) => (self.last - next).checked_add(1),
+            None => Some(0),
+        };
+        match remaining.map(usize::try_from) {
+            Some(Ok(remaining)) => (remaining, Some(remaining)),
+            _ => (usize::MAX, None),
+        }
+    }
+}
+
+/// All of [`BitBaggable::VARIANTS`], in the given order
+pub(crate) fn variants_in<PossibleFlagsT: BitBaggable>(order: Order) -> Variants<PossibleFlagsT> {
+    Variants {
+        order,
+        yielded: 0,
+        last: None,
+        _flags: core::marker::PhantomData,
+    }
+}
+
+pub(crate) struct Variants<PossibleFlagsT: BitBaggable> {
+    order: Order,
+    yielded: usize,
+    /// For [`Order::Ascending`], the key of the last variant yielded.
+    ///
+    /// Selecting the next variant each time is quadratic, but doesn't allocate.
+    last: Option<(u32, usize)>,
+    // `fn` so `Variants` is always `Send` and `Sync`
+    _flags: core::marker::PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Iterator for Variants<PossibleFlagsT> {
+    type Item = &'static (&'static str, PossibleFlagsT, PossibleFlagsT::ReprT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.order {
+            Order::Declaration => {
+                let variant = PossibleFlagsT::VARIANTS.get(self.yielded)?;
+                self.yielded += 1;
+                Some(variant)
+            }
+            Order::Ascending => {
+                let last = self.last;
+                let (variant, key) = PossibleFlagsT::VARIANTS
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, variant)| (variant, (variant.2.trailing_zeros(), ix)))
+                    .filter(|(_, key)| match last {
+                        Some(last) => *key > last,
+                        None => true,
+                    })
+                    .min_by_key(|(_, key)| *key)?;
+                self.last = Some(key);
+                self.yielded += 1;
+                Some(variant)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = PossibleFlagsT::VARIANTS.len() - self.yielded;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> ExactSizeIterator for Variants<PossibleFlagsT> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bitbag;
+
+    #[derive(Debug, Clone, Copy, PartialEq, BitBaggable)]
+    #[bitbag(order = "ascending")]
+    #[repr(u8)]
+    enum Spec {
+        C = 0b0100,
+        A = 0b0001,
+        B = 0b0010,
+    }
+
+    #[test]
+    fn ascending() {
+        let bag = BitBag::<Spec>::all();
+        assert_eq!(
+            bag.into_iter().collect::<Vec<_>>(),
+            [Spec::A, Spec::B, Spec::C]
+        );
+        assert_eq!(bag.to_string(), "A | B | C");
+    }
+
+    #[test]
+    fn declaration() {
+        let bag = BitBag::<Spec>::all();
+        assert_eq!(
+            bag.iter_in(Order::Declaration).collect::<Vec<_>>(),
+            [Spec::C, Spec::A, Spec::B]
+        );
+        assert_eq!(bag.display_in(Order::Declaration).to_string(), "C | A | B");
+    }
+
+    #[test]
+    fn ascending_ties() {
+        #[derive(Debug, Clone, PartialEq, BitBaggable)]
+        #[repr(u8)]
+        enum Overlapping {
+            High = 0b1000,
+            Both = 0b0011,
+            Low = 0b0001,
+        }
+        let bag = BitBag::<Overlapping>::all();
+        assert_eq!(
+            bag.iter_in(Order::Ascending).collect::<Vec<_>>(),
+            [Overlapping::Both, Overlapping::Low, Overlapping::High]
+        );
+    }
+
+    #[test]
+    fn single_bits() {
+        #[derive(Debug, Clone, Copy, PartialEq, BitBaggable)]
+        #[repr(i8)]
+        enum Signed {
+            Low = 0b0000_0001,
+            Mid = 0b0001_0000,
+            Sign = -0b1000_0000,
+        }
+        assert_eq!((Signed::SINGLE_BITS, Spec::SINGLE_BITS), (true, false));
+        assert_eq!(Signed::variant_at_bit(4), Some(1));
+        assert_eq!(Signed::variant_at_bit(5), None);
+
+        let bag = BitBag::<Signed>::new(-0b0111_1111);
+        assert_eq!(
+            bag.into_iter().collect::<Vec<_>>(),
+            [Signed::Low, Signed::Sign]
+        );
+        assert_eq!(bag.to_string(), "Low | Sign");
+        assert_eq!(BitBag::<Signed>::all().into_iter().count(), 3);
+    }
+
+    #[test]
+    fn subsets() {
+        let subsets = BitBag::<Spec>::all_subsets();
+        assert_eq!(subsets.size_hint(), (8, Some(8)));
+        let mut reprs = subsets.map(|bag| bag.get()).collect::<Vec<_>>();
+        assert_eq!(reprs.len(), 8);
+        reprs.sort();
+        assert_eq!(reprs, (0..8).collect::<Vec<_>>());
+    }
+
+    #[derive(Clone, Copy)]
+    enum Repeated {
+        Bit,
+    }
+
+    /// `LEN` entries for the same variant, each with its own bit, which is enough to count subsets
+    const fn repeated<const LEN: usize>() -> [(&'static str, Repeated, u128); LEN] {
+        let mut variants = [("Bit", Repeated::Bit, 0); LEN];
+        let mut ix = 0;
+        while ix < LEN {
+            variants[ix].2 = 1 << ix;
+            ix += 1;
+        }
+        variants
+    }
+
+    impl BitBaggable for Repeated {
+        type ReprT = u128;
+        fn into_repr(self) -> u128 {
+            1
+        }
+        const VARIANTS: &'static [(&'static str, Self, u128)] = &repeated::<128>();
+    }
+
+    #[test]
+    fn subsets_128() {
+        let mut subsets = BitBag::<Repeated>::all_subsets();
+        assert_eq!(subsets.last, u128::MAX);
+        assert_eq!(subsets.size_hint(), (usize::MAX, None));
+        assert_eq!(subsets.next().unwrap().get(), 0);
+
+        // Skip to the end, which needs every bit
+        subsets.next = Some(u128::MAX - 1);
+        assert_eq!(subsets.size_hint(), (2, Some(2)));
+        assert_eq!(subsets.next().unwrap().get(), u128::MAX - 1);
+        assert_eq!(subsets.next().unwrap().get(), u128::MAX);
+        assert!(subsets.next().is_none());
+        assert_eq!(subsets.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    #[should_panic = "at most 128 variants"]
+    fn subsets_129() {
+        #[derive(Clone, Copy)]
+        enum TooMany {
+            Bit,
+        }
+
+        impl BitBaggable for TooMany {
+            type ReprT = u128;
+            fn into_repr(self) -> u128 {
+                1
+            }
+            const VARIANTS: &'static [(&'static str, Self, u128)] =
+                &[("Bit", TooMany::Bit, 1); 129];
+        }
+
+        let _ = BitBag::<TooMany>::all_subsets();
+    }
+
+    #[test]
+    fn names() {
+        let bag = BitBag::<Spec>::new(0b1101);
+        assert_eq!(bag.iter_names().collect::<Vec<_>>(), ["A", "C"]);
+        let bag = BitBag::<crate::tests::FooFlags>::new(0b1010);
+        assert_eq!(bag.iter_names().collect::<Vec<_>>(), ["B", "D"]);
+    }
+    #[test]
+    fn map_flags() {
+        use crate::tests::FooFlags;
+
+        let bag = BitBag::<FooFlags>::new(0b1_1011);
+        let mapped = bag.map_flags(|flag| match flag {
+            FooFlags::A => Some(Spec::C),
+            FooFlags::B => Some(Spec::A),
+            FooFlags::C => Some(Spec::B),
+            FooFlags::D => None,
+        });
+        assert_eq!(mapped.get(), 0b0101);
+        assert!(BitBag::<FooFlags>::empty()
+            .map_flags(|_| Some(Spec::A))
+            .is_empty());
     }
 }
@@ -58,7 +58,7 @@
 //! #     C = 0b0100,
 //! # }
 //! BitBag::<Flags>::new_strict(0b1000).unwrap_err();
-//! // "The bits 0b1000 are not accounted for in the enum Flags"
+//! // "The bits 0b1000 are not accounted for in the enum Flags (bit 3, nearest flag C)"
 //!
 //! let bag = BitBag::<Flags>::new(0b0110);
 //! for flag in bag {
@@ -69,16 +69,237 @@
 //!     }
 //! };
 //! ```
+//! Bags can be parsed from the same format they are displayed in, and raw numbers are accepted too.
+//! ```
+//! # use bitbag::{BitBag, BitBaggable};
+//! # #[derive(BitBaggable, Debug, Clone)]
+//! # #[repr(u8)]
+//! # enum Flags {
+//! #     A = 0b0001,
+//! #     B = 0b0010,
+//! #     C = 0b0100,
+//! # }
+//! let bag: BitBag<Flags> = "A | 0b0100".parse().unwrap();
+//! assert_eq!(bag.to_string(), "A | C");
+//!
+//! "A | 0x8".parse::<BitBag<Flags>>().unwrap_err();
+//! // "The bits 0b1000 are not accounted for in the enum Flags (bit 3, nearest flag C)"
+//! ```
+//!
+//! # Features
+//! Without the default `std` feature the crate is `no_std`: bags, their operators, iteration and [`Display`] only need `core`.
+//! Integrations which need an allocator or `std` enable those features themselves.
+//! - `alloc`: parsing with [`FromStr`](core::str::FromStr), [`BitBag::to_hex_string`], and the [`testing`](mod@testing) helpers.
+//! - `arbitrary`: `Arbitrary` implementations for fuzzing, generating declared flags only or any bits, in [`arbitrary`](mod@arbitrary).
+//! - `async-graphql`: a GraphQL scalar holding a list of flag names.
+//! - `binrw`: `BinRead` and `BinWrite` implementations, in the endianness of the surrounding struct.
+//! - `bitflags`: conversions to and from bitflags 2.x types with the same bits, and a macro pairing the two, in [`bitflags`](mod@bitflags).
+//! - `bitvec`: conversions to and from `BitArray`, and `BitSlice` views over the inner primitive.
+//! - `bnum`: reprs wider than `u128`, with bit-index discriminants, in [`bnum`](mod@bnum).
+//! - `borsh`: `BorshSerialize` and `BorshDeserialize` implementations, encoding the inner primitive.
+//! - `bytemuck`: `Pod` and `Zeroable` implementations, so bags can be cast to and from byte buffers.
+//! - `clap`: a value parser for comma separated flag names, with the names as possible values, in [`clap`](mod@clap).
+//! - `critical-section`: [`SharedBitBag`], a bag for sharing with interrupt handlers on targets without atomics.
+//! - `defmt`: a `defmt::Format` implementation, logging interned flag names like `Display`.
+//! - `deku`: `DekuRead`, `DekuWrite` and `DekuSize` implementations, forwarding endianness and bit widths to the inner primitive.
+//! - `diesel`: `ToSql`, `FromSql` and `AsExpression` implementations for the integer SQL types, and a strict wrapper in [`diesel`](mod@diesel).
+//! - `enumflags2`: conversions to and from enumflags2 `BitFlags`, for enums which are also `#[bitflags]`, in [`enumflags2`](mod@enumflags2).
+//! - `flagset`: checked conversions between bags and flagset `FlagSet`s with the same bits, in [`flagset`](mod@flagset).
+//! - `garde`: conversions from [`MissingFlags`] and [`ForbiddenFlags`] to `garde::Error`, for `#[garde(custom(...))]` rules.
+//! - `inventory`: a [`registry`](mod@registry) of enums, for tooling which enumerates and renders every flag type in the binary.
+//! - `modular-bitfield`: a `Specifier` implementation, so bags can be fields of `#[bitfield]` structs.
+//! - `nix`: `From` and `TryFrom` conversions between the [`os`](mod@os) enums' bags and nix's `OFlag`, `ProtFlags`, `MapFlags` and `MsgFlags`.
+//! - `no-panic`: link-time checks that the core operations can't panic, run with `cargo test --profile no-panic --features no-panic`.
+//! - `nom`: parsers for binary protocols in [`nom`](mod@nom).
+//! - `num_enum`: decoding single flags with num_enum's `TryFromPrimitive`, in [`num_enum`](mod@num_enum).
+//! - `os`: bags for libc's `O_*`, `PROT_*`, `MAP_*` and `MSG_*` flags on unix targets, in [`os`](mod@os).
+//! - `portable-atomic`: back [`AtomicBitBag`] with portable-atomic, for targets without native atomics, and for `u128` reprs.
+//! - `postgres-types`: `ToSql` and `FromSql` implementations for Postgres `BIT` and `VARBIT` columns.
+//! - `proptest`: strategies for bags of declared flags, and single flags, in [`proptest`](mod@proptest).
+//! - `protobuf`: conversions to and from prost-style integer and `repeated` enum fields in [`protobuf`](mod@protobuf).
+//! - `pyo3`: extracting bags from Python ints, `IntFlag`s and lists of names, and converting them to ints or names, in [`pyo3`](mod@pyo3).
+//! - `quickcheck`: an `Arbitrary` implementation generating declared flags only, and shrinking by unsetting flags.
+//! - `rand`: sampling valid bags from `StandardUniform`, and single flags, in [`rand`](mod@rand).
+//! - `redis`: `ToRedisArgs` and `FromRedisValue` implementations storing an integer, with strict and masking wrappers in [`redis`](mod@redis).
+//! - `rkyv`: zero-copy archiving, validating that archived bags only contain recognised flags, in [`rkyv`](mod@rkyv).
+//! - `rusqlite`: `ToSql` and `FromSql` implementations storing an `INTEGER`, rejecting unrecognised bits on read.
+//! - `rustix`: `From` and `TryFrom` conversions between the [`os`](mod@os) enums' bags and rustix's `OFlags`, `ProtFlags`, `MapFlags`, `SendFlags` and `RecvFlags`.
+//! - `schemars`: a `JsonSchema` implementation matching the human-readable serde representation.
+//! - `serde`: `Serialize` and `Deserialize` implementations, and representation helpers in [`serde`](mod@serde).
+//! - `simd`: use `core::simd` for [`WideBitBag`] operations, which needs a nightly compiler.
+//! - `sqlx`: `Type`, `Encode` and `Decode` implementations using the inner primitive's column type, and a strict wrapper in [`sqlx`](mod@sqlx).
+//! - `std` (default): [`DynBitBag`], whose universe of flag names is a `HashMap`. Implies `alloc`.
+//! - `strum`: reuse strum's name tables in [`strum`](mod@strum).
+//! - `svd2rust`: macros adapting svd2rust register and field readers and writers in [`svd2rust`](mod@svd2rust).
+//! - `tock-registers`: read, write and modify tock-registers registers as bags, with the extension traits in [`tock_registers`](mod@tock_registers).
+//! - `uniffi`: a macro registering bags with UniFFI, as bits or as a sequence of the enum, in [`uniffi`](mod@uniffi).
+//! - `utoipa`: a `ToSchema` implementation matching the human-readable serde representation.
+//! - `validator`: conversions from [`MissingFlags`] and [`ForbiddenFlags`] to `ValidationError`, naming the flags in its params.
+//! - `valuable`: a `Valuable` implementation listing the set flags' names, for structured logging with tracing.
+//! - `wasm-bindgen`: conversions to and from `JsValue` numbers and `BigInt`s, and name arrays in [`wasm_bindgen`](mod@wasm_bindgen).
+//! - `windows-sys`: a macro declaring enums of windows-sys constants, like access rights, in [`windows_sys`](mod@windows_sys).
+//! - `zerocopy`: `FromBytes`, `IntoBytes`, `KnownLayout` and `Immutable`, so bags can be embedded in zerocopy packet structs.
+//!   Any bit pattern is a valid bag, with unrecognised bits preserved as by [`BitBag::new`].
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "async-graphql")]
+mod async_graphql;
+pub mod atomic;
+#[cfg(feature = "binrw")]
+mod binrw;
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
+#[cfg(feature = "bitvec")]
+mod bitvec;
 mod bitwise;
+#[cfg(feature = "bnum")]
+pub mod bnum;
+#[cfg(feature = "borsh")]
+mod borsh;
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+mod bytes;
+mod cell;
+#[cfg(feature = "clap")]
+pub mod clap;
+mod const_ops;
+#[cfg(feature = "defmt")]
+mod defmt;
+#[cfg(feature = "deku")]
+mod deku;
+#[cfg(feature = "diesel")]
+pub mod diesel;
+#[cfg(feature = "std")]
+mod dynamic;
+#[cfg(feature = "enumflags2")]
+pub mod enumflags2;
+pub mod ffi;
+#[cfg(feature = "flagset")]
+pub mod flagset;
+mod format;
+mod frozen;
+#[cfg(feature = "garde")]
+mod garde;
 mod impls;
 mod iter;
-pub use bitbag_derive::{check, BitBaggable, BitOr};
-use num::{PrimInt, Zero as _};
-use std::{
+#[cfg(feature = "modular-bitfield")]
+mod modular_bitfield;
+#[cfg(all(feature = "nix", unix))]
+mod nix;
+#[cfg(all(test, feature = "no-panic", not(debug_assertions)))]
+mod no_panic;
+#[cfg(feature = "nom")]
+pub mod nom;
+#[cfg(feature = "num_enum")]
+pub mod num_enum;
+#[cfg(all(feature = "os", unix))]
+pub mod os;
+#[cfg(feature = "alloc")]
+mod parse;
+mod policy;
+#[cfg(feature = "postgres-types")]
+mod postgres_types;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+#[cfg(feature = "pyo3")]
+pub mod pyo3;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rand")]
+pub mod rand;
+#[cfg(feature = "redis")]
+pub mod redis;
+mod register;
+#[cfg(feature = "inventory")]
+pub mod registry;
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(all(feature = "rustix", unix))]
+mod rustix;
+#[cfg(feature = "schemars")]
+mod schemars;
+mod scoped;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "critical-section")]
+mod shared;
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
+#[cfg(feature = "strum")]
+pub mod strum;
+#[cfg(feature = "svd2rust")]
+pub mod svd2rust;
+#[cfg(feature = "alloc")]
+pub mod testing;
+#[cfg(feature = "tock-registers")]
+pub mod tock_registers;
+#[cfg(feature = "uniffi")]
+pub mod uniffi;
+// The tests' custom types need a `UniFfiTag` at the crate root
+#[cfg(all(test, feature = "uniffi"))]
+::uniffi::setup_scaffolding!();
+#[cfg(feature = "utoipa")]
+mod utoipa;
+#[cfg(feature = "validator")]
+mod validator;
+#[cfg(feature = "valuable")]
+mod valuable;
+#[cfg(kani)]
+mod verification;
+mod volatile;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm_bindgen;
+mod watched;
+mod wide;
+#[cfg(feature = "windows-sys")]
+pub mod windows_sys;
+pub use atomic::AtomicBitBag;
+pub use bitbag_derive::{check, BitBaggable, BitOr, WideBitBaggable};
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub use defmt::__defmt;
+
+/// Without the `defmt` feature, the derive leaves out `BitBaggable::defmt_name`.
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __defmt_name {
+    ($($tt:tt)*) => {};
+}
+pub use bytes::{BufferTooShort, DecodeError};
+pub use cell::BitBagCell;
+use core::{
     any::type_name,
     fmt::{self, Binary, Debug, Display},
     ops::{BitAnd as _, BitOr as _, BitXor as _, Not as _},
 };
+#[cfg(feature = "std")]
+pub use dynamic::{DuplicateFlag, DynBitBag, FlagUniverse, UnknownFlag};
+pub use format::{DisplayIn, Dump, LazyNames, PaddedBinary};
+pub use frozen::FrozenBitBag;
+pub use iter::Order;
+use num::{One as _, PrimInt, Zero as _};
+#[cfg(feature = "alloc")]
+pub use parse::{ParseError, Parsed, UnknownNames};
+pub use policy::{ForbiddenFlags, MissingFlags};
+pub use register::FlagRegister;
+pub use scoped::ScopedFlag;
+#[cfg(feature = "critical-section")]
+pub use shared::SharedBitBag;
+pub use volatile::VolatileBitBag;
+pub use watched::{Change, WatchedBitBag};
+pub use wide::{WideBitBag, WideBitBaggable, WideDecodeError, WideNonFlagBits};
 
 /// The trait that allows an enum to be placed inside a [`BitBag`].
 ///
@@ -90,10 +311,93 @@ pub trait BitBaggable: Sized + 'static {
     fn into_repr(self) -> Self::ReprT;
     /// names, values and discriminants
     const VARIANTS: &'static [(&'static str, Self, Self::ReprT)];
+    /// The order to iterate over, and display flags in
+    const ORDER: Order = Order::Declaration;
+    /// How bags of this enum are serialized, which can be changed with `#[bitbag(serde = "names")]` on the derive
+    const ENCODING: Encoding = Encoding::Auto;
+    /// The name of this variant, as listed in [`BitBaggable::VARIANTS`].
+    ///
+    /// The derive implements this with a `match`, rather than searching [`BitBaggable::VARIANTS`].
+    fn name(self) -> &'static str {
+        let repr = self.into_repr();
+        Self::VARIANTS
+            .iter()
+            .find(|(_, _, candidate)| *candidate == repr)
+            .map(|(name, _, _)| *name)
+            .expect("every variant is listed in VARIANTS")
+    }
+    /// The name of this variant, interned for [defmt](https://docs.rs/defmt), so logging a bag only sends string indices.
+    ///
+    /// The derive implements this with `defmt::intern!`.
+    /// The default returns [`None`], and the name is sent as a string instead.
+    #[cfg(feature = "defmt")]
+    fn defmt_name(&self) -> Option<::defmt::Str> {
+        None
+    }
+    /// The index in [`BitBaggable::VARIANTS`] of the variant called `name`.
+    ///
+    /// The derive implements this with a binary search over a table sorted by name, rather than scanning [`BitBaggable::VARIANTS`].
+    fn variant_index(name: &str) -> Option<usize> {
+        Self::VARIANTS
+            .iter()
+            .position(|(candidate, _, _)| *candidate == name)
+    }
+    /// Whether every variant is a single, distinct bit, declared from lowest to highest.
+    ///
+    /// Iteration then walks the set bits with [`BitBaggable::variant_at_bit`], rather than testing every variant.
+    /// The derive works this out at compile time.
+    const SINGLE_BITS: bool = false;
+    /// The index in [`BitBaggable::VARIANTS`] of the variant which is exactly bit `bit`.
+    ///
+    /// The derive implements this with a table, rather than searching [`BitBaggable::VARIANTS`].
+    fn variant_at_bit(bit: u32) -> Option<usize> {
+        let repr = Self::ReprT::one().unsigned_shl(bit);
+        Self::VARIANTS
+            .iter()
+            .position(|(_, _, candidate)| *candidate == repr)
+    }
+    /// Every bit which is part of a variant, which bags are validated against.
+    ///
+    /// The derive implements this with a constant, rather than combining [`BitBaggable::VARIANTS`],
+    /// which the [`const_contains!`] family of macros also check their operands against.
+    fn all_bits() -> Self::ReprT {
+        Self::VARIANTS
+            .iter()
+            .fold(Self::ReprT::zero(), |accumulator, (_, _, repr)| {
+                accumulator.bitor(*repr)
+            })
+    }
+}
+
+/// How a bag is serialized, see [`BitBaggable::ENCODING`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Encoding {
+    /// Flag names for human-readable formats, the inner primitive for binary formats
+    #[default]
+    Auto,
+    /// Flag names, for every format
+    Names,
+    /// The inner primitive, for every format
+    Bits,
 }
 
 /// Wraps a primitive, with helper methods for checking and setting flags.
+///
+/// # Layout
+/// This is `#[repr(transparent)]`, so it's guaranteed to have the same size, alignment and ABI as [`BitBaggable::ReprT`],
+/// and can be used in DMA descriptors, FFI structs, and pointer casts wherever the primitive could.
+/// For helper functions which C callers can use with the same flags, see [`ffi`](mod@ffi).
+/// The derive also asserts this at compile time for each enum.
 #[repr(transparent)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromBytes,
+        zerocopy::IntoBytes,
+        zerocopy::KnownLayout,
+        zerocopy::Immutable
+    )
+)]
 pub struct BitBag<PossibleFlagsT: BitBaggable> {
     pub repr: PossibleFlagsT::ReprT,
 }
@@ -117,6 +421,41 @@ impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
         *Self::empty().set_all()
     }
 
+    /// New bag with only `flag` set
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// assert_eq!(BitBag::just(Flags::B).get(), 0b0010);
+    /// assert_eq!(BitBag::of([Flags::A, Flags::B]).get(), 0b0011);
+    /// assert_eq!(BitBag::<Flags>::of([]), BitBag::empty());
+    /// ```
+    pub fn just(flag: PossibleFlagsT) -> Self {
+        Self {
+            repr: flag.into_repr(),
+        }
+    }
+
+    /// New bag with each of `flags` set, see [`BitBag::just`]
+    pub fn of(flags: impl IntoIterator<Item = PossibleFlagsT>) -> Self {
+        let mut bag = Self::empty();
+        for flag in flags {
+            bag.set(flag);
+        }
+        bag
+    }
+
+    /// New bag, discarding any bits which aren't defined in the enum
+    pub fn new_masked(prim: PossibleFlagsT::ReprT) -> Self {
+        Self {
+            repr: prim.bitand(mask::<PossibleFlagsT>()),
+        }
+    }
+
     /// Check the bits of `prim`, and return a [`NonFlagBits`] error if it has bits set which aren't defined in the enum.
     pub fn new_strict(prim: PossibleFlagsT::ReprT) -> Result<Self, NonFlagBits<PossibleFlagsT>> {
         match unrecognised_bits::<PossibleFlagsT>(prim) {
@@ -149,6 +488,47 @@ impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
     }
 }
 
+/// Matching
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Whether every bit set in `other` is also set in `self`
+    pub fn contains_all(&self, other: Self) -> bool {
+        self.is_set_raw(other.repr)
+    }
+
+    /// Whether any bit set in `other` is also set in `self`
+    pub fn contains_any(&self, other: Self) -> bool {
+        !self.repr.bitand(other.repr).is_zero()
+    }
+
+    /// Check several conditions at once, for use in match guards.
+    ///
+    /// Every flag in `all_of` must be set, at least one in `any_of` must be set (unless it is empty), and none in `none_of` may be set.
+    /// ```
+    /// # use bitbag::{bag, BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Perm {
+    /// #     Read = 0b0001,
+    /// #     Write = 0b0010,
+    /// #     Exec = 0b0100,
+    /// #     Sticky = 0b1000,
+    /// # }
+    /// let describe = |perms: BitBag<Perm>| match perms {
+    ///     p if p.matches(bag!(Perm::Read), bag!(Perm::Write | Perm::Exec), bag!(Perm::Sticky)) => "usable",
+    ///     p if p.is_empty() => "none",
+    ///     _ => "restricted",
+    /// };
+    /// assert_eq!(describe(BitBag::new(0b0011)), "usable");
+    /// assert_eq!(describe(BitBag::new(0b1011)), "restricted");
+    /// assert_eq!(describe(BitBag::new(0b0001)), "restricted");
+    /// ```
+    pub fn matches(&self, all_of: Self, any_of: Self, none_of: Self) -> bool {
+        self.contains_all(all_of)
+            && (any_of.is_empty() || self.contains_any(any_of))
+            && !self.contains_any(none_of)
+    }
+}
+
 /// Builder
 impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
     pub fn set(&mut self, flag: PossibleFlagsT) -> &mut Self {
@@ -172,6 +552,11 @@ impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
         self
     }
 
+    /// Clear the bag, returning the bits which were set
+    pub fn take(&mut self) -> Self {
+        core::mem::replace(self, Self::empty())
+    }
+
     pub fn unset_raw(&mut self, raw: PossibleFlagsT::ReprT) -> &mut Self {
         self.repr = self.repr.bitand(raw.not());
         self
@@ -181,14 +566,59 @@ impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
         self.unset_raw(flag.into_repr())
     }
 
+    /// Apply `f`, but keep the bits which aren't defined in the enum (such as reserved register bits) as they were
+    pub fn modify(&mut self, f: impl FnOnce(&mut Self) -> &mut Self) -> &mut Self {
+        let mask = mask::<PossibleFlagsT>();
+        let mut bag = *self;
+        f(&mut bag);
+        self.repr = bag.repr.bitand(mask).bitor(self.repr.bitand(mask.not()));
+        self
+    }
+
     /// Get a copy of the inner primitive
     pub const fn get(&self) -> PossibleFlagsT::ReprT {
         self.repr
     }
 }
 
+/// Set-like, named as the std collections are
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Set `flag`, returning whether any of its bits weren't already set
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let mut bag = BitBag::empty();
+    /// assert!(bag.insert(Flags::A));
+    /// assert!(!bag.insert(Flags::A));
+    /// assert!(bag.contains(Flags::A));
+    /// assert!(bag.remove(Flags::A));
+    /// assert!(!bag.remove(Flags::A));
+    /// ```
+    pub fn insert(&mut self, flag: PossibleFlagsT) -> bool {
+        let before = self.repr;
+        self.set(flag);
+        self.repr != before
+    }
+
+    /// Unset `flag`, returning whether any of its bits were set
+    pub fn remove(&mut self, flag: PossibleFlagsT) -> bool {
+        let before = self.repr;
+        self.unset(flag);
+        self.repr != before
+    }
+
+    /// Whether all of `flag`'s bits are set, as [`BitBag::is_set`]
+    pub fn contains(&self, flag: PossibleFlagsT) -> bool {
+        self.is_set(flag)
+    }
+}
+
 /// The error returned when calling a [`BitBag`] from a primitive which contains bits set which aren't represented by flags
-#[derive(Debug)]
 #[non_exhaustive]
 pub struct NonFlagBits<PossibleFlagsT: BitBaggable> {
     unrecognised: PossibleFlagsT::ReprT,
@@ -199,12 +629,37 @@ impl<PossibleFlagsT: BitBaggable> NonFlagBits<PossibleFlagsT> {
     pub fn unrecognised(&self) -> PossibleFlagsT::ReprT {
         self.unrecognised
     }
+
+    /// The positions of the unrecognised bits, lowest first
+    pub fn bits(&self) -> impl Iterator<Item = u32> {
+        set_bits(self.unrecognised)
+    }
+
+    /// The name of the declared flag with a bit closest to position `bit`, preferring the lower bit on ties.
+    ///
+    /// A hint for which flag was meant, say if a mask is off by one.
+    pub fn nearest_flag(&self, bit: u32) -> Option<&'static str> {
+        PossibleFlagsT::VARIANTS
+            .iter()
+            .flat_map(|(name, _, repr)| set_bits(*repr).map(move |flag_bit| (flag_bit, *name)))
+            .min_by_key(|(flag_bit, _)| (flag_bit.abs_diff(bit), *flag_bit))
+            .map(|(_, name)| name)
+    }
 }
 
-impl<PossibleFlagsT: BitBaggable> std::error::Error for NonFlagBits<PossibleFlagsT>
+impl<PossibleFlagsT: BitBaggable> Debug for NonFlagBits<PossibleFlagsT>
 where
-    PossibleFlagsT::ReprT: Binary + Debug,
-    PossibleFlagsT: Debug,
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonFlagBits")
+            .field("unrecognised", &self.unrecognised)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for NonFlagBits<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Binary + Debug
 {
 }
 
@@ -212,21 +667,41 @@ impl<PossibleFlagsT: BitBaggable> Display for NonFlagBits<PossibleFlagsT>
 where
     PossibleFlagsT::ReprT: Binary,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "The bits {:#b} are not accounted for in the enum {}",
             self.unrecognised,
             type_name::<PossibleFlagsT>()
-        )
+        )?;
+        for (ix, bit) in self.bits().enumerate() {
+            f.write_str(if ix == 0 { " (" } else { "; " })?;
+            write!(f, "bit {bit}")?;
+            if let Some(name) = self.nearest_flag(bit) {
+                write!(f, ", nearest flag {name}")?;
+            }
+        }
+        if !self.unrecognised.is_zero() {
+            f.write_str(")")?;
+        }
+        Ok(())
     }
 }
 
+/// The positions of the bits set in `repr`, lowest first
+fn set_bits<ReprT: PrimInt>(mut repr: ReprT) -> impl Iterator<Item = u32> {
+    core::iter::from_fn(move || {
+        if repr.is_zero() {
+            return None;
+        }
+        let bit = repr.trailing_zeros();
+        repr = repr & !ReprT::one().unsigned_shl(bit);
+        Some(bit)
+    })
+}
+
 fn mask<PossibleFlagsT: BitBaggable>() -> PossibleFlagsT::ReprT {
-    PossibleFlagsT::VARIANTS.iter().fold(
-        PossibleFlagsT::ReprT::zero(),
-        |accumulator, (_, _, repr)| accumulator.bitor(*repr),
-    )
+    PossibleFlagsT::all_bits()
 }
 
 fn unrecognised_bits<PossibleFlagsT: BitBaggable>(
@@ -235,27 +710,102 @@ fn unrecognised_bits<PossibleFlagsT: BitBaggable>(
     let mask = mask::<PossibleFlagsT>();
     match mask.bitor(repr) == mask {
         true => None,
-        false => Some(mask.bitor(repr).bitxor(repr)),
+        false => Some(mask.bitor(repr).bitxor(mask)),
+    }
+}
+
+/// The bits of `repr`, reinterpreted as unsigned, so an `i8` of `-1` is `0xff`.
+///
+/// [`None`] if they don't fit in a `u128`.
+#[cfg(feature = "protobuf")]
+pub(crate) fn repr_to_bits<ReprT: PrimInt>(repr: ReprT) -> Option<u128> {
+    if let Some(bits) = repr.to_u128() {
+        return Some(bits);
+    }
+    let width = ReprT::zero().count_zeros();
+    match width <= 128 {
+        true => Some(repr.to_i128()? as u128 & (u128::MAX >> (128 - width))),
+        false => None,
+    }
+}
+
+/// The name of the schema, or GraphQL scalar, for bags of `PossibleFlagsT`, e.g. `BitBag_my_crate_Flags`.
+///
+/// This is built from the full path of the enum, so enums with the same name in different modules don't collide.
+/// Anything which isn't valid in a schema name, like `::` and `<`, becomes `_`.
+#[cfg(any(feature = "async-graphql", feature = "schemars", feature = "utoipa"))]
+pub(crate) fn schema_name<PossibleFlagsT>() -> alloc::string::String {
+    let path = type_name::<PossibleFlagsT>().replace("::", "_");
+    alloc::format!(
+        "BitBag_{}",
+        path.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "_")
+    )
+}
+
+/// Reinterpret unsigned `bits` as a `ReprT`, so `0xff` is an `i8` of `-1`.
+///
+/// [`None`] if `bits` is wider than `ReprT`.
+#[cfg(feature = "alloc")]
+pub(crate) fn repr_from_bits<ReprT: PrimInt>(bits: u128) -> Option<ReprT> {
+    let width = ReprT::zero().count_zeros();
+    if width < 128 && bits >> width != 0 {
+        return None;
+    }
+    let signed = ReprT::min_value() < ReprT::zero();
+    match signed && width <= 128 && bits >> (width - 1) & 1 == 1 {
+        // Sign-extend, so the cast sees a negative number
+        true => <ReprT as num::NumCast>::from(((bits << (128 - width)) as i128) >> (128 - width)),
+        false => <ReprT as num::NumCast>::from(bits),
     }
 }
 
 impl<PossibleFlagsT: BitBaggable> fmt::Display for BitBag<PossibleFlagsT> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_names_with(f, PossibleFlagsT::ORDER, |(name, _, _)| name)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Write the `A | B` form, as [`Display`] does, to any [`fmt::Write`].
+    ///
+    /// This never allocates, so it suits fixed-capacity buffers like `heapless::String`.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable, BitOr};
+    /// # #[derive(BitBaggable, BitOr)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let mut out = String::new();
+    /// (Flags::A | Flags::B).write_names(&mut out).unwrap();
+    /// assert_eq!(out, "A | B");
+    /// ```
+    pub fn write_names(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        self.write_names_with(w, PossibleFlagsT::ORDER, |(name, _, _)| name)
+    }
+
+    /// Write the `A | B` form, taking each flag's name from `name_of`
+    pub(crate) fn write_names_with<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        order: Order,
+        name_of: impl Fn(&'static (&'static str, PossibleFlagsT, PossibleFlagsT::ReprT)) -> &'a str,
+    ) -> fmt::Result {
         if self.is_empty() {
             return f.write_str("<unset>");
         }
 
         let mut first = true;
 
-        for (name, _, repr) in PossibleFlagsT::VARIANTS {
-            if self.is_set_raw(*repr) {
-                match first {
-                    true => {
-                        f.write_fmt(format_args!("{name}"))?;
-                        first = false
-                    }
-                    false => f.write_fmt(format_args!(" | {name}"))?,
+        for variant in iter::set_variants(*self, order) {
+            let name = name_of(variant);
+            match first {
+                true => {
+                    f.write_fmt(format_args!("{name}"))?;
+                    first = false
                 }
+                false => f.write_fmt(format_args!(" | {name}"))?,
             }
         }
 
@@ -307,13 +857,199 @@ pub(crate) mod tests {
     #[test]
     fn fail_new_single_non_flag() {
         let res = BitBag::<FooFlags>::new_strict(0b1000_0000);
-        assert!(matches!(res, Err(_)));
+        assert!(res.is_err());
     }
 
     #[test]
     fn fail_new_mixed() {
         let res = BitBag::<FooFlags>::new_strict(0b1000_0001);
-        assert!(matches!(res, Err(_)));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn non_flag_bits() {
+        let e = BitBag::<FooFlags>::new_strict(0b0011_0001).unwrap_err();
+        assert_eq!(e.bits().collect::<Vec<_>>(), [4, 5]);
+        assert_eq!(e.nearest_flag(4), Some("D"));
+        assert_eq!(e.nearest_flag(0), Some("A"));
+        assert_eq!(
+            e.to_string(),
+            "The bits 0b110000 are not accounted for in the enum bitbag::tests::FooFlags \
+             (bit 4, nearest flag D; bit 5, nearest flag D)"
+        );
+        let boxed: Box<dyn core::error::Error> = Box::new(e);
+        assert!(boxed.source().is_none());
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
+    fn bare_enum() {
+        // None of `Debug`, `Clone`, `PartialEq` or `Hash` for the enum itself
+        #[derive(BitBaggable, BitOr)]
+        #[repr(u8)]
+        enum Bare {
+            A = 0b01,
+            B = 0b10,
+        }
+
+        let bag = Bare::A | Bare::B;
+        let copy = bag;
+        assert_eq!(bag.clone(), copy);
+        assert_eq!(format!("{bag:?}"), "BitBag { repr: 3 }");
+        assert_eq!(BitBag::<Bare>::default(), BitBag::empty());
+
+        let e = BitBag::<Bare>::new_strict(0b100).unwrap_err();
+        assert_eq!(format!("{e:?}"), "NonFlagBits { unrecognised: 4 }");
+        let _: Box<dyn core::error::Error> = Box::new(e);
+    }
+
+    #[test]
+    fn unrecognised() {
+        let bag = BitBag::<FooFlags>::new(0b1000_0001);
+        assert_eq!(bag.unrecognised_bits(), Some(0b1000_0000));
+        let bag = BitBag::<FooFlags>::new(0b0000_0001);
+        assert_eq!(bag.unrecognised_bits(), None);
+    }
+
+    #[test]
+    #[cfg(any(feature = "async-graphql", feature = "schemars", feature = "utoipa"))]
+    fn schema_name() {
+        assert_eq!(
+            super::schema_name::<FooFlags>(),
+            "BitBag_bitbag_tests_FooFlags"
+        );
+        assert_eq!(
+            super::schema_name::<Option<FooFlags>>(),
+            "BitBag_core_option_Option_bitbag_tests_FooFlags_"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "protobuf")]
+    fn repr_bits_unsigned() {
+        assert_eq!(repr_to_bits(-1i8), Some(0xff));
+        assert_eq!(repr_to_bits(i32::MIN), Some(0x8000_0000));
+        assert_eq!(repr_to_bits(-1i128), Some(u128::MAX));
+        assert_eq!(repr_to_bits(0x7fu8), Some(0x7f));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn repr_bits() {
+        assert_eq!(repr_from_bits::<i8>(0xff), Some(-1));
+        assert_eq!(repr_from_bits::<i32>(0x8000_0000), Some(i32::MIN));
+        assert_eq!(repr_from_bits::<i128>(u128::MAX), Some(-1));
+        assert_eq!(repr_from_bits::<u8>(0xff), Some(0xff));
+        assert_eq!(repr_from_bits::<i8>(0x100), None);
+        assert_eq!(repr_from_bits::<u16>(0x1_0000), None);
+    }
+
+    #[test]
+    fn masked() {
+        let bag = BitBag::<FooFlags>::new_masked(0b1000_0001);
+        assert_eq!(bag.get(), 0b0000_0001);
+        assert!(!bag.has_unrecognised_bits());
+    }
+
+    #[test]
+    fn matching() {
+        use FooFlags::*;
+
+        let bag = A | C;
+        assert!(bag.contains_all(A | C));
+        assert!(!bag.contains_all(A | B));
+        assert!(bag.contains_any(B | C));
+        assert!(!bag.contains_any(B | D));
+        assert!(bag.matches(BitBag::just(A), B | C, BitBag::just(D)));
+        assert!(bag.matches(BitBag::empty(), BitBag::empty(), BitBag::empty()));
+        assert!(!bag.matches(BitBag::empty(), B | D, BitBag::empty()));
+        assert!(!bag.matches(BitBag::empty(), BitBag::empty(), C | D));
+    }
+
+    #[test]
+    fn write_names() {
+        /// A fixed buffer, which fails rather than growing
+        struct Fixed {
+            buf: [u8; 8],
+            len: usize,
+        }
+
+        impl fmt::Write for Fixed {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let end = self.len + s.len();
+                self.buf
+                    .get_mut(self.len..end)
+                    .ok_or(fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+
+        let mut out = Fixed {
+            buf: [0; 8],
+            len: 0,
+        };
+        (FooFlags::A | FooFlags::C).write_names(&mut out).unwrap();
+        assert_eq!(&out.buf[..out.len], b"A | C");
+        let mut out = Fixed {
+            buf: [0; 8],
+            len: 0,
+        };
+        assert!(BitBag::<FooFlags>::all().write_names(&mut out).is_err());
+    }
+
+    #[test]
+    fn all_bits() {
+        const VALID: bool = FooFlags::__bitbag_is_valid(0b1001);
+        const INVALID: bool = FooFlags::__bitbag_is_valid(0b1_0000);
+        assert_eq!((VALID, INVALID), (true, false));
+        assert_eq!(FooFlags::all_bits(), 0b1111);
+        assert_eq!(BitBag::<FooFlags>::all().get(), 0b1111);
+    }
+
+    #[test]
+    fn derive_leaves_inherent_names_free() {
+        use crate as bitbag;
+
+        #[derive(Clone, Copy, BitBaggable)]
+        #[repr(u8)]
+        enum Own {
+            A = 1,
+        }
+
+        impl Own {
+            const fn is_valid(repr: u8) -> bool {
+                repr == 1
+            }
+        }
+
+        assert!(Own::is_valid(1));
+        assert!(const_contains!(Own, 1, 1));
+    }
+
+    #[test]
+    fn layout() {
+        use std::mem::{align_of, size_of};
+        assert_eq!(size_of::<BitBag<FooFlags>>(), size_of::<u8>());
+        assert_eq!(align_of::<BitBag<FooFlags>>(), align_of::<u8>());
+        let bags = [BitBag::<FooFlags>::new(0b0001), BitBag::new(0b0110)];
+        let reprs: &[u8; 2] = unsafe { &*(&bags as *const _ as *const [u8; 2]) };
+        assert_eq!(reprs, &[0b0001, 0b0110]);
+    }
+
+    #[test]
+    fn take() {
+        let mut bag = BitBag::<FooFlags>::new(0b1000_0011);
+        assert_eq!(bag.take().get(), 0b1000_0011);
+        assert!(bag.is_empty());
+    }
+
+    #[test]
+    fn modify() {
+        let mut bag = BitBag::<FooFlags>::new(0b1000_0011);
+        bag.modify(|bag| bag.set_raw(0b0111_0000).unset(FooFlags::A));
+        assert_eq!(bag.get(), 0b1000_0010);
     }
 
     #[test]
@@ -331,6 +1067,69 @@ pub(crate) mod tests {
         assert_eq!(bag.get(), 0b0000_0011);
     }
 
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Session {
+            id: u32,
+            caps: BitBag<FooFlags>,
+        }
+
+        let session = Session::default();
+        assert_eq!(session.id, 0);
+        assert!(session.caps.is_empty());
+        assert_eq!(session.caps, BitBag::empty());
+    }
+
+    #[test]
+    fn just_and_of() {
+        assert_eq!(BitBag::just(FooFlags::C).get(), 0b0100);
+        assert_eq!(
+            BitBag::of([FooFlags::A, FooFlags::D, FooFlags::A]).get(),
+            0b1001
+        );
+        assert_eq!(
+            BitBag::of(FooFlags::A | FooFlags::B),
+            FooFlags::A | FooFlags::B
+        );
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut bag = FooFlags::A | FooFlags::B;
+        assert!(!bag.insert(FooFlags::B));
+        assert!(bag.insert(FooFlags::C));
+        assert_eq!(bag, FooFlags::A | FooFlags::B | FooFlags::C);
+        assert!(bag.remove(FooFlags::A));
+        assert!(!bag.remove(FooFlags::D));
+        assert!(!bag.contains(FooFlags::A));
+        assert!(bag.contains(FooFlags::C));
+        assert_eq!(bag, FooFlags::B | FooFlags::C);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut bags = [
+            FooFlags::C | FooFlags::D,
+            BitBag::just(FooFlags::A),
+            BitBag::empty(),
+        ];
+        bags.sort();
+        assert_eq!(
+            bags,
+            [
+                BitBag::empty(),
+                BitBag::just(FooFlags::A),
+                FooFlags::C | FooFlags::D
+            ]
+        );
+        assert!(BitBag::just(FooFlags::D) > FooFlags::A | FooFlags::B | FooFlags::C);
+        assert_eq!(
+            BitBag::<FooFlags>::new(7).cmp(&BitBag::new(7)),
+            core::cmp::Ordering::Equal
+        );
+    }
+
     #[test]
     fn manually_unset() {
         let mut bag = BitBag::<FooFlags>::new(0b0000_0011);
@@ -338,6 +1137,12 @@ pub(crate) mod tests {
         assert_eq!(bag.get(), 0b0000_0010);
     }
 
+    #[test]
+    fn name() {
+        assert_eq!(FooFlags::A.name(), "A");
+        assert_eq!(FooFlags::D.name(), "D");
+    }
+
     #[test]
     fn display() {
         let bitbag = FooFlags::A | FooFlags::B;
@@ -345,4 +1150,22 @@ pub(crate) mod tests {
         let bitbag = BitBag::<FooFlags>::default();
         assert_eq!("<unset>", bitbag.to_string());
     }
+
+    #[test]
+    #[cfg(feature = "zerocopy")]
+    fn zerocopy() {
+        use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+        #[derive(FromBytes, IntoBytes, KnownLayout, Immutable)]
+        #[repr(C)]
+        struct Packet {
+            kind: u8,
+            flags: BitBag<FooFlags>,
+        }
+
+        let packet = Packet::read_from_bytes(&[7, 0b0000_0101]).unwrap();
+        assert_eq!(packet.kind, 7);
+        assert_eq!(packet.flags, FooFlags::A | FooFlags::C);
+        assert_eq!(packet.as_bytes(), [7, 0b0000_0101]);
+    }
 }
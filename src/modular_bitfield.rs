@@ -0,0 +1,71 @@
+//! [modular-bitfield](https://docs.rs/modular-bitfield) support, enabled by the `modular-bitfield` feature.
+//!
+//! A [`BitBag`] can be a field of a `#[bitfield]` struct, taking up the full width of its inner primitive.
+//! Reading permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+//! ```
+//! # use bitbag::{BitBag, BitBaggable, BitOr};
+//! use modular_bitfield::prelude::*;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     Ack = 0b01,
+//!     Syn = 0b10,
+//! }
+//!
+//! #[bitfield]
+//! struct Header {
+//!     flags: BitBag<Flags>,
+//!     window: B24,
+//! }
+//!
+//! let header = Header::new().with_flags(Flags::Ack | Flags::Syn).with_window(1024);
+//! assert_eq!(header.flags(), Flags::Ack | Flags::Syn);
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::modular_bitfield::{
+    error::{InvalidBitPattern, OutOfBounds},
+    Specifier,
+};
+
+impl<PossibleFlagsT: BitBaggable> Specifier for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Specifier<InOut = PossibleFlagsT::ReprT>,
+{
+    const BITS: usize = <PossibleFlagsT::ReprT as Specifier>::BITS;
+    type Bytes = <PossibleFlagsT::ReprT as Specifier>::Bytes;
+    type InOut = Self;
+
+    fn into_bytes(input: Self) -> Result<Self::Bytes, OutOfBounds> {
+        PossibleFlagsT::ReprT::into_bytes(input.repr)
+    }
+
+    fn from_bytes(bytes: Self::Bytes) -> Result<Self, InvalidBitPattern<Self::Bytes>> {
+        PossibleFlagsT::ReprT::from_bytes(bytes).map(BitBag::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::modular_bitfield::prelude::*;
+
+    #[bitfield]
+    struct Packet {
+        kind: B4,
+        flags: BitBag<FooFlags>,
+        len: B4,
+    }
+
+    #[test]
+    fn field() {
+        let packet = Packet::new().with_kind(0xa).with_flags(A | D).with_len(0x5);
+        assert_eq!(packet.into_bytes(), [0x9a, 0x50]);
+
+        let packet = Packet::from_bytes([0x0a, 0x5f]);
+        assert_eq!(packet.flags(), BitBag::new(0xf0));
+        assert_eq!((packet.kind(), packet.len()), (0xa, 0x5));
+    }
+}
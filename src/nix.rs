@@ -0,0 +1,62 @@
+//! Conversions between the [`os`](crate::os) enums and [nix](https://docs.rs/nix)'s flag types, enabled by the `nix` feature.
+
+use crate::{
+    os::{MapFlags, MsgFlags, OpenFlags, ProtFlags},
+    BitBag, NonFlagBits,
+};
+use core::convert::TryFrom;
+
+macro_rules! impl_conversions {
+    ($($flags:ty => $nix:ty),* $(,)?) => {$(
+        impl From<BitBag<$flags>> for $nix {
+            fn from(bag: BitBag<$flags>) -> Self {
+                <$nix>::from_bits_retain(bag.get())
+            }
+        }
+
+        impl TryFrom<$nix> for BitBag<$flags> {
+            type Error = NonFlagBits<$flags>;
+
+            /// Fails if `flags` has bits which aren't defined in the enum
+            fn try_from(flags: $nix) -> Result<Self, Self::Error> {
+                BitBag::new_strict(flags.bits())
+            }
+        }
+    )*};
+}
+
+impl_conversions!(
+    OpenFlags => ::nix::fcntl::OFlag,
+    ProtFlags => ::nix::sys::mman::ProtFlags,
+    MapFlags => ::nix::sys::mman::MapFlags,
+    MsgFlags => ::nix::sys::socket::MsgFlags,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::nix::{fcntl::OFlag, sys::mman, sys::socket};
+
+    #[test]
+    fn roundtrip() {
+        let flags = OFlag::from(OpenFlags::Append | OpenFlags::ReadWrite);
+        assert_eq!(flags, OFlag::O_APPEND | OFlag::O_RDWR);
+        assert_eq!(
+            BitBag::try_from(flags).unwrap(),
+            OpenFlags::Append | OpenFlags::ReadWrite
+        );
+        assert_eq!(
+            mman::MapFlags::from(MapFlags::Private | MapFlags::Anonymous),
+            mman::MapFlags::MAP_PRIVATE | mman::MapFlags::MAP_ANON
+        );
+        assert_eq!(
+            BitBag::try_from(socket::MsgFlags::MSG_DONTWAIT).unwrap(),
+            BitBag::just(MsgFlags::DontWait)
+        );
+    }
+
+    #[test]
+    fn unrecognised() {
+        assert!(BitBag::<ProtFlags>::try_from(mman::ProtFlags::from_bits_retain(1 << 30)).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Checks that the core operations can't panic, enabled by the `no-panic` feature.
+//!
+//! Each operation is wrapped in a [`#[no_panic]`](::no_panic::no_panic) function for a concrete enum.
+//! Linking fails, naming the function, unless the optimizer proves that no panicking path remains.
+//! The proof needs optimizations, so the module is left out of debug builds, and a single codegen unit to inline generic code reliably,
+//! so run them in the `no-panic` profile: `cargo test --profile no-panic --features no-panic`.
+//!
+//! The operations covered are construction, the flag and bitwise operations, the checked constructors and queries,
+//! and iteration over set flags and their names.
+//! Formatting isn't covered, since it goes through [`core::fmt`], which may panic in the writer.
+
+use crate::tests::FooFlags;
+use crate::BitBag;
+use ::no_panic::no_panic;
+
+type Bag = BitBag<FooFlags>;
+
+#[no_panic]
+fn constructors(prim: u8) -> (Bag, Bag, Bag, Bag, Option<Bag>) {
+    (
+        Bag::new(prim),
+        Bag::empty(),
+        Bag::all(),
+        Bag::new_masked(prim),
+        Bag::new_strict(prim).ok(),
+    )
+}
+
+#[no_panic]
+fn flags(mut bag: Bag, flag: FooFlags) -> (bool, Bag) {
+    let set = bag.is_set(flag);
+    bag.set(flag)
+        .unset(FooFlags::A)
+        .set_raw(0b0100)
+        .unset_raw(0b1000);
+    bag.set_all().clear_all();
+    (set, bag)
+}
+
+#[no_panic]
+fn bitwise(left: Bag, right: Bag, flag: FooFlags) -> (Bag, Bag, Bag, Bag, Bag) {
+    let mut assigned = left;
+    assigned |= right;
+    assigned &= flag;
+    (left | right, left & right, !left, left | flag, assigned)
+}
+
+#[no_panic]
+fn queries(bag: Bag, other: Bag) -> (bool, Option<u8>, bool, bool, bool, u8) {
+    (
+        bag.is_empty(),
+        bag.unrecognised_bits(),
+        bag.contains_all(other),
+        bag.contains_any(other),
+        bag.matches(other, other, !other),
+        bag.get(),
+    )
+}
+
+#[no_panic]
+fn iteration(bag: Bag) -> (usize, usize) {
+    (
+        bag.into_iter().count(),
+        bag.iter_names().map(str::len).sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::*;
+
+    #[test]
+    fn core_operations() {
+        assert_eq!(constructors(0xf1).4, None);
+        assert_eq!(flags(A | B, B), (true, Bag::empty()));
+        assert_eq!(bitwise(A | B, Bag::just(C), C).4, A | B | C);
+        assert!(queries(A | B, Bag::just(A)).2);
+        assert_eq!(iteration(A | C), (2, 2));
+    }
+}
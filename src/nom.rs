@@ -0,0 +1,110 @@
+//! [nom](https://docs.rs/nom) parsers which read a repr-sized field from a byte slice.
+//! ```
+//! use bitbag::{BitBag, BitBaggable};
+//! use nom::{number::Endianness, IResult};
+//!
+//! #[derive(BitBaggable)]
+//! #[repr(u16)]
+//! enum Flags {
+//!     A = 0x0001,
+//!     B = 0x0100,
+//! }
+//!
+//! fn header(input: &[u8]) -> IResult<&[u8], BitBag<Flags>> {
+//!     bitbag::nom::flags(Endianness::Big)(input)
+//! }
+//!
+//! let (rest, bag) = header(&[0x01, 0x01, 0xFF]).unwrap();
+//! assert_eq!(bag.get(), 0x0101);
+//! assert_eq!(rest, [0xFF]);
+//! ```
+
+use crate::{bytes::repr_from_bytes, BitBag, BitBaggable};
+use core::mem::size_of;
+use nom::{
+    bytes::complete::take,
+    error::{ErrorKind, ParseError},
+    number::Endianness,
+    IResult, Parser as _,
+};
+
+/// Read a `PossibleFlagsT::ReprT` with the given endianness, permitting (and preserving) unrecognised bits.
+pub fn flags<'a, PossibleFlagsT, E>(
+    endianness: Endianness,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], BitBag<PossibleFlagsT>, E>
+where
+    PossibleFlagsT: BitBaggable,
+    E: ParseError<&'a [u8]>,
+{
+    move |input| {
+        let (rest, bytes) = take(size_of::<PossibleFlagsT::ReprT>()).parse(input)?;
+        Ok((rest, BitBag::new(from_bytes(bytes, endianness))))
+    }
+}
+
+/// Like [`flags`], but fails with [`ErrorKind::Verify`] if there are bits set which aren't defined in the enum.
+pub fn flags_strict<'a, PossibleFlagsT, E>(
+    endianness: Endianness,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], BitBag<PossibleFlagsT>, E>
+where
+    PossibleFlagsT: BitBaggable,
+    E: ParseError<&'a [u8]>,
+{
+    move |input| {
+        let (rest, bag) = flags(endianness)(input)?;
+        match bag.has_unrecognised_bits() {
+            true => Err(nom::Err::Error(E::from_error_kind(
+                input,
+                ErrorKind::Verify,
+            ))),
+            false => Ok((rest, bag)),
+        }
+    }
+}
+
+/// Resolve [`Endianness::Native`], and assemble the repr
+fn from_bytes<ReprT: num::PrimInt>(bytes: &[u8], endianness: Endianness) -> ReprT {
+    let little = match endianness {
+        Endianness::Big => false,
+        Endianness::Little => true,
+        Endianness::Native => cfg!(target_endian = "little"),
+    };
+    repr_from_bytes(bytes, little)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bitbag;
+    use crate::tests::FooFlags;
+
+    #[derive(BitBaggable)]
+    #[repr(i32)]
+    enum Signed {
+        Low = 0x0000_0001,
+        High = i32::MIN,
+    }
+
+    #[test]
+    fn endianness() {
+        let input = [0x01, 0x00, 0x00, 0x80, 0xAA];
+        let (rest, bag) = flags::<Signed, ()>(Endianness::Little)(&input).unwrap();
+        assert_eq!(rest, [0xAA]);
+        assert!(bag.is_set(Signed::Low) && bag.is_set(Signed::High));
+
+        let (_, bag) = flags::<Signed, ()>(Endianness::Big)(&input).unwrap();
+        assert_eq!(bag.get(), 0x0100_0080);
+    }
+
+    #[test]
+    fn strict() {
+        let (_, bag) = flags_strict::<FooFlags, ()>(Endianness::Big)(&[0x05]).unwrap();
+        assert_eq!(bag.get(), 0x05);
+        assert!(flags_strict::<FooFlags, ()>(Endianness::Big)(&[0x10]).is_err());
+    }
+
+    #[test]
+    fn incomplete() {
+        assert!(flags::<Signed, ()>(Endianness::Big)(&[0x00, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Reuse [num_enum](https://docs.rs/num_enum)'s `TryFromPrimitive` for decoding single flags, enabled by the `num_enum` feature.
+//!
+//! If your enum also derives `TryFromPrimitive`, these functions decode with its `match`,
+//! and report its error type, rather than searching [`BitBaggable::VARIANTS`].
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use num_enum::TryFromPrimitive;
+//!
+//! #[derive(Debug, PartialEq, BitBaggable, BitOr, TryFromPrimitive)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! assert_eq!(bitbag::num_enum::flag_from_repr::<Perm>(0b0010).unwrap(), Perm::Write);
+//! assert!(bitbag::num_enum::flag_from_repr::<Perm>(0b0011).is_err());
+//!
+//! assert_eq!(bitbag::num_enum::to_flag(BitBag::just(Perm::Read)).unwrap(), Perm::Read);
+//! assert!(bitbag::num_enum::to_flag(Perm::Read | Perm::Write).is_err());
+//!
+//! let bag = bitbag::num_enum::from_reprs::<Perm>([0b0001, 0b0010]).unwrap();
+//! assert_eq!(bag, Perm::Read | Perm::Write);
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::num_enum::TryFromPrimitive;
+
+/// The variant whose value is exactly `repr`
+pub fn flag_from_repr<PossibleFlagsT>(
+    repr: PossibleFlagsT::ReprT,
+) -> Result<PossibleFlagsT, <PossibleFlagsT as TryFromPrimitive>::Error>
+where
+    PossibleFlagsT:
+        BitBaggable + TryFromPrimitive<Primitive = <PossibleFlagsT as BitBaggable>::ReprT>,
+{
+    PossibleFlagsT::try_from_primitive(repr)
+}
+
+/// The variant whose value is exactly the bits of `bag`, failing if it holds no flags, several, or unrecognised bits
+pub fn to_flag<PossibleFlagsT>(
+    bag: BitBag<PossibleFlagsT>,
+) -> Result<PossibleFlagsT, <PossibleFlagsT as TryFromPrimitive>::Error>
+where
+    PossibleFlagsT:
+        BitBaggable + TryFromPrimitive<Primitive = <PossibleFlagsT as BitBaggable>::ReprT>,
+{
+    flag_from_repr(bag.get())
+}
+
+/// Collect a bag from the values of individual flags, failing on the first which isn't a variant
+pub fn from_reprs<PossibleFlagsT>(
+    reprs: impl IntoIterator<Item = PossibleFlagsT::ReprT>,
+) -> Result<BitBag<PossibleFlagsT>, <PossibleFlagsT as TryFromPrimitive>::Error>
+where
+    PossibleFlagsT:
+        BitBaggable + TryFromPrimitive<Primitive = <PossibleFlagsT as BitBaggable>::ReprT>,
+{
+    let mut bag = BitBag::empty();
+    for repr in reprs {
+        bag.set(flag_from_repr(repr)?);
+    }
+    Ok(bag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bitbag;
+    use crate::BitOr;
+    use ::num_enum::TryFromPrimitiveError;
+
+    #[derive(Debug, PartialEq, BitBaggable, BitOr, TryFromPrimitive)]
+    #[repr(u16)]
+    enum Mode {
+        Read = 0b0001,
+        Write = 0b0010,
+        ReadWrite = 0b0011,
+    }
+
+    #[test]
+    fn single_flags() {
+        assert_eq!(flag_from_repr::<Mode>(0b0011).unwrap(), Mode::ReadWrite);
+        assert_eq!(
+            flag_from_repr::<Mode>(0b0100).unwrap_err(),
+            TryFromPrimitiveError::<Mode>::new(0b0100)
+        );
+        assert_eq!(to_flag(Mode::Read | Mode::Write).unwrap(), Mode::ReadWrite);
+        assert!(to_flag(BitBag::<Mode>::empty()).is_err());
+    }
+
+    #[test]
+    fn collect() {
+        assert_eq!(from_reprs::<Mode>([]).unwrap(), BitBag::empty());
+        assert_eq!(
+            from_reprs::<Mode>([0b0010, 0b0001]).unwrap(),
+            Mode::Read | Mode::Write
+        );
+        assert!(from_reprs::<Mode>([0b0001, 0b1000]).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Ready-made enums for common libc flag families, enabled by the `os` feature on unix targets.
+//!
+//! Each enum is `#[repr(i32)]`, with the platform's libc constants as discriminants,
+//! so [`BitBag::get`](crate::BitBag::get) is the `c_int` the syscall expects.
+//! Only flags which every unix target defines are included, and zero-valued constants,
+//! like `O_RDONLY` and `PROT_NONE`, are an empty bag.
+//! With the `rustix` or `nix` feature, these bags convert to and from those crates' flag types, with [`From`] and [`TryFrom`](core::convert::TryFrom).
+//! ```
+//! use bitbag::{os::OpenFlags, BitBag};
+//!
+//! let flags = OpenFlags::ReadWrite | OpenFlags::CloseOnExec;
+//! let fd = unsafe { libc::open(b"/dev/null\0".as_ptr().cast(), flags.get()) };
+//! assert!(fd >= 0);
+//! unsafe { libc::close(fd) };
+//! ```
+
+use crate as bitbag;
+use crate::{BitBaggable, BitOr};
+
+/// `O_*` flags for `open`, without `O_RDONLY`, which is the empty access mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BitBaggable, BitOr)]
+#[repr(i32)]
+pub enum OpenFlags {
+    WriteOnly = libc::O_WRONLY,
+    ReadWrite = libc::O_RDWR,
+    Create = libc::O_CREAT,
+    Exclusive = libc::O_EXCL,
+    NoCtty = libc::O_NOCTTY,
+    Truncate = libc::O_TRUNC,
+    Append = libc::O_APPEND,
+    NonBlock = libc::O_NONBLOCK,
+    Sync = libc::O_SYNC,
+    Directory = libc::O_DIRECTORY,
+    NoFollow = libc::O_NOFOLLOW,
+    CloseOnExec = libc::O_CLOEXEC,
+}
+
+/// `PROT_*` memory protections for `mmap` and `mprotect`, without `PROT_NONE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BitBaggable, BitOr)]
+#[repr(i32)]
+pub enum ProtFlags {
+    Read = libc::PROT_READ,
+    Write = libc::PROT_WRITE,
+    Exec = libc::PROT_EXEC,
+}
+
+/// `MAP_*` flags for `mmap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BitBaggable, BitOr)]
+#[repr(i32)]
+pub enum MapFlags {
+    Shared = libc::MAP_SHARED,
+    Private = libc::MAP_PRIVATE,
+    Fixed = libc::MAP_FIXED,
+    Anonymous = libc::MAP_ANON,
+}
+
+/// `MSG_*` flags for `send` and `recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BitBaggable, BitOr)]
+#[repr(i32)]
+pub enum MsgFlags {
+    OutOfBand = libc::MSG_OOB,
+    Peek = libc::MSG_PEEK,
+    DontRoute = libc::MSG_DONTROUTE,
+    Truncated = libc::MSG_TRUNC,
+    WaitAll = libc::MSG_WAITALL,
+    DontWait = libc::MSG_DONTWAIT,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitBag;
+
+    #[test]
+    fn values() {
+        let flags = OpenFlags::Create | OpenFlags::Truncate | OpenFlags::WriteOnly;
+        assert_eq!(flags.get(), libc::O_CREAT | libc::O_TRUNC | libc::O_WRONLY);
+        assert_eq!(flags.to_string(), "WriteOnly | Create | Truncate");
+        let prot = ProtFlags::Read | ProtFlags::Write;
+        assert_eq!(prot.get(), libc::PROT_READ | libc::PROT_WRITE);
+        assert!(BitBag::<MsgFlags>::new_strict(libc::MSG_PEEK | libc::MSG_WAITALL).is_ok());
+    }
+
+    #[test]
+    fn mmap() {
+        let prot = ProtFlags::Read | ProtFlags::Write;
+        let flags = MapFlags::Private | MapFlags::Anonymous;
+        let len = 4096;
+        // SAFETY: an anonymous private mapping doesn't alias any memory, and is unmapped before returning
+        unsafe {
+            let ptr = libc::mmap(core::ptr::null_mut(), len, prot.get(), flags.get(), -1, 0);
+            assert_ne!(ptr, libc::MAP_FAILED);
+            ptr.cast::<u8>().write(1);
+            assert_eq!(libc::munmap(ptr, len), 0);
+        }
+    }
+}
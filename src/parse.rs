@@ -0,0 +1,544 @@
+//! Parse a [`BitBag`] from the `A | B` form produced by its [`Display`] implementation.
+//!
+//! Tokens may also be raw numbers (`12`, `0x3`, `0b101`, `0o7`), which are checked against the declared bits.
+
+use crate::{repr_from_bits, unrecognised_bits, BitBag, BitBaggable, NonFlagBits};
+use alloc::{string::String, vec::Vec};
+use core::{
+    any::type_name,
+    fmt::{self, Binary, Debug, Display},
+    str::FromStr,
+};
+use num::Num as _;
+
+impl<PossibleFlagsT: BitBaggable> FromStr for BitBag<PossibleFlagsT> {
+    type Err = ParseError<PossibleFlagsT>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with(s, UnknownNames::Error).map(|parsed| parsed.bag)
+    }
+}
+
+/// Parsing
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Like [`BitBag::from_str`](FromStr::from_str), but names which aren't flags are handled according to `policy`.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable, UnknownNames};
+    /// # #[derive(BitBaggable, Debug)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let parsed = BitBag::<Flags>::from_str_with("A | Typo | B", UnknownNames::Collect).unwrap();
+    /// assert_eq!(parsed.bag.get(), 0b0011);
+    /// assert_eq!(parsed.unknown, ["Typo"]);
+    /// ```
+    pub fn from_str_with(
+        s: &str,
+        policy: UnknownNames,
+    ) -> Result<Parsed<PossibleFlagsT>, ParseError<PossibleFlagsT>> {
+        parse_with(s, by_name::<PossibleFlagsT>, policy)
+    }
+
+    /// Build a bag from already split tokens, each a flag name or a number as in [`BitBag::from_str`](FromStr::from_str).
+    ///
+    /// Surrounding whitespace is ignored, and the first bad token is the error.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable, ParseError};
+    /// # #[derive(BitBaggable, Debug)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0b0001,
+    /// #     B = 0b0010,
+    /// # }
+    /// let bag = BitBag::<Flags>::from_names(["A", " B"]).unwrap();
+    /// assert_eq!(bag.get(), 0b0011);
+    ///
+    /// let from_env = "A,B".split(',');
+    /// assert_eq!(BitBag::<Flags>::from_names(from_env).unwrap(), bag);
+    ///
+    /// let e = BitBag::<Flags>::from_names(["A", "Typo"]).unwrap_err();
+    /// assert!(matches!(e, ParseError::UnknownName(name) if name == "Typo"));
+    /// ```
+    pub fn from_names<NameT: AsRef<str>>(
+        names: impl IntoIterator<Item = NameT>,
+    ) -> Result<Self, ParseError<PossibleFlagsT>> {
+        let mut bag = Self::empty();
+        for name in names {
+            let repr = parse_token(name.as_ref().trim(), by_name::<PossibleFlagsT>)?;
+            bag.set_raw(repr);
+        }
+        Ok(bag)
+    }
+
+    /// Parse a hex mask, with or without a leading `0x`, rejecting bits which aren't defined in the enum.
+    ///
+    /// Signed reprs are read bit-for-bit, as written by [`BitBag::to_hex_string`], so `0xff` is an `i8` of `-1`.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable, Debug)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     A = 0x01,
+    /// #     B = 0x10,
+    /// # }
+    /// let bag = BitBag::<Flags>::from_hex_str("0x11").unwrap();
+    /// assert_eq!(bag.to_hex_string(), "0x11");
+    ///
+    /// BitBag::<Flags>::from_hex_str("0x1F").unwrap_err();
+    /// ```
+    pub fn from_hex_str(s: &str) -> Result<Self, ParseError<PossibleFlagsT>> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+        let digits = match s.get(..2) {
+            Some("0x" | "0X") => &s[2..],
+            _ => s,
+        };
+        match parse_digits::<PossibleFlagsT>(digits, 16) {
+            Ok(repr) => check_bits(repr).map(Self::new),
+            Err(()) => Err(ParseError::InvalidNumeric(String::from(s))),
+        }
+    }
+}
+
+/// What to do with names which aren't flags when parsing.
+///
+/// Numeric tokens are always checked, regardless of policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnknownNames {
+    /// Fail with [`ParseError::UnknownName`]
+    Error,
+    /// Ignore them
+    Skip,
+    /// Ignore them, but record them in [`Parsed::unknown`]
+    Collect,
+}
+
+/// Returned by [`BitBag::from_str_with`]
+pub struct Parsed<PossibleFlagsT: BitBaggable> {
+    /// The recognised flags
+    pub bag: BitBag<PossibleFlagsT>,
+    /// The names which weren't recognised, if [`UnknownNames::Collect`] was chosen
+    pub unknown: Vec<String>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for Parsed<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parsed")
+            .field("bag", &self.bag)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+/// Look a flag up in [`BitBaggable::VARIANTS`]
+pub(crate) fn by_name<PossibleFlagsT: BitBaggable>(name: &str) -> Option<PossibleFlagsT::ReprT> {
+    PossibleFlagsT::variant_index(name).map(|ix| PossibleFlagsT::VARIANTS[ix].2)
+}
+
+/// Parse the `A | B` form, resolving names with `by_name`
+pub(crate) fn parse_with<PossibleFlagsT: BitBaggable>(
+    s: &str,
+    by_name: impl Fn(&str) -> Option<PossibleFlagsT::ReprT>,
+    policy: UnknownNames,
+) -> Result<Parsed<PossibleFlagsT>, ParseError<PossibleFlagsT>> {
+    let s = s.trim();
+    let mut parsed = Parsed {
+        bag: BitBag::empty(),
+        unknown: Vec::new(),
+    };
+    if s.is_empty() || s == "<unset>" {
+        return Ok(parsed);
+    }
+    for token in s.split('|') {
+        match (
+            parse_token::<PossibleFlagsT>(token.trim(), &by_name),
+            policy,
+        ) {
+            (Ok(repr), _) => {
+                parsed.bag.set_raw(repr);
+            }
+            (Err(ParseError::UnknownName(_)), UnknownNames::Skip) => {}
+            (Err(ParseError::UnknownName(name)), UnknownNames::Collect) => {
+                parsed.unknown.push(name)
+            }
+            (Err(e), _) => return Err(e),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Resolve a single token, either by name, or as a number
+pub(crate) fn parse_token<PossibleFlagsT: BitBaggable>(
+    token: &str,
+    by_name: impl Fn(&str) -> Option<PossibleFlagsT::ReprT>,
+) -> Result<PossibleFlagsT::ReprT, ParseError<PossibleFlagsT>> {
+    if token.is_empty() {
+        return Err(ParseError::EmptyInput);
+    }
+    if let Some(repr) = by_name(token) {
+        return Ok(repr);
+    }
+    match parse_numeric::<PossibleFlagsT>(token) {
+        Some(Ok(repr)) => check_bits(repr),
+        Some(Err(())) => Err(ParseError::InvalidNumeric(String::from(token))),
+        None => Err(ParseError::UnknownName(String::from(token))),
+    }
+}
+
+fn check_bits<PossibleFlagsT: BitBaggable>(
+    repr: PossibleFlagsT::ReprT,
+) -> Result<PossibleFlagsT::ReprT, ParseError<PossibleFlagsT>> {
+    match unrecognised_bits::<PossibleFlagsT>(repr) {
+        Some(unrecognised) => Err(ParseError::NonFlagBits(NonFlagBits { unrecognised })),
+        None => Ok(repr),
+    }
+}
+
+/// [`None`] if `token` doesn't look like a number at all
+fn parse_numeric<PossibleFlagsT: BitBaggable>(
+    token: &str,
+) -> Option<Result<PossibleFlagsT::ReprT, ()>> {
+    let (digits, radix) = match token.get(..2) {
+        Some("0x" | "0X") => (&token[2..], 16),
+        Some("0b" | "0B") => (&token[2..], 2),
+        Some("0o" | "0O") => (&token[2..], 8),
+        _ if token.starts_with(|c: char| c.is_ascii_digit()) => (token, 10),
+        _ => return None,
+    };
+    Some(parse_digits::<PossibleFlagsT>(digits, radix))
+}
+
+fn parse_digits<PossibleFlagsT: BitBaggable>(
+    digits: &str,
+    radix: u32,
+) -> Result<PossibleFlagsT::ReprT, ()> {
+    // `from_str_radix` accepts a leading sign, which doesn't make sense for a mask
+    if !digits.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err(());
+    }
+    // Allow `0x00_FF`, in keeping with rust literals
+    let digits = digits.replace('_', "");
+    // Read the digits as unsigned bits, so `0xff` is an `i8` of `-1`, as printed by `{:#x}`
+    u128::from_str_radix(&digits, radix)
+        .ok()
+        .and_then(repr_from_bits)
+        // Reprs wider than `u128` are unsigned
+        .or_else(|| PossibleFlagsT::ReprT::from_str_radix(&digits, radix).ok())
+        .ok_or(())
+}
+
+/// The error returned when parsing a [`BitBag`] from a string fails
+#[non_exhaustive]
+pub enum ParseError<PossibleFlagsT: BitBaggable> {
+    /// The token was neither a flag name, nor a number
+    UnknownName(String),
+    /// The token looked like a number, but couldn't be parsed as the enum's repr
+    InvalidNumeric(String),
+    /// The token was a number with bits set which aren't defined in the enum
+    NonFlagBits(NonFlagBits<PossibleFlagsT>),
+    /// There was nothing to parse where a token was expected, like between the bars of `A || B`
+    EmptyInput,
+}
+
+impl<PossibleFlagsT: BitBaggable> ParseError<PossibleFlagsT> {
+    /// For [`ParseError::UnknownName`], the declared flag with the closest name, if any is close enough to be a likely typo.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable, Debug)]
+    /// # #[repr(u8)]
+    /// # enum Flags {
+    /// #     Read = 0b0001,
+    /// #     Write = 0b0010,
+    /// # }
+    /// let e = "Read | Wrte".parse::<BitBag<Flags>>().unwrap_err();
+    /// assert_eq!(e.suggestion(), Some("Write"));
+    /// assert!(e.to_string().ends_with(r#", did you mean "Write"?"#));
+    /// ```
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            ParseError::UnknownName(name) => suggest::<PossibleFlagsT>(name),
+            _ => None,
+        }
+    }
+}
+
+/// Names within a third of the token's length in edits, ignoring case, are suggested
+fn suggest<PossibleFlagsT: BitBaggable>(token: &str) -> Option<&'static str> {
+    let token: Vec<char> = token.chars().map(|c| c.to_ascii_lowercase()).collect();
+    PossibleFlagsT::VARIANTS
+        .iter()
+        .map(|(name, _, _)| {
+            let candidate: Vec<char> = name.chars().map(|c| c.to_ascii_lowercase()).collect();
+            (edit_distance(&token, &candidate), *name)
+        })
+        .filter(|(distance, _)| *distance <= token.len() / 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Levenshtein distance, keeping a single row of the table
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for ParseError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownName(name) => f.debug_tuple("UnknownName").field(name).finish(),
+            ParseError::InvalidNumeric(token) => {
+                f.debug_tuple("InvalidNumeric").field(token).finish()
+            }
+            ParseError::NonFlagBits(e) => f.debug_tuple("NonFlagBits").field(e).finish(),
+            ParseError::EmptyInput => f.write_str("EmptyInput"),
+        }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for ParseError<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Binary + Debug
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for ParseError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownName(name) => {
+                write!(
+                    f,
+                    "{name:?} is not a flag in the enum {}",
+                    type_name::<PossibleFlagsT>()
+                )?;
+                match self.suggestion() {
+                    Some(suggestion) => write!(f, ", did you mean {suggestion:?}?"),
+                    None => Ok(()),
+                }
+            }
+            ParseError::InvalidNumeric(token) => write!(
+                f,
+                "{token:?} is not a valid number for the enum {}",
+                type_name::<PossibleFlagsT>()
+            ),
+            ParseError::NonFlagBits(e) => Display::fmt(e, f),
+            ParseError::EmptyInput => {
+                f.write_str("expected a flag name or number, but found nothing")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    #[test]
+    fn names() {
+        assert_eq!("A | C".parse::<BitBag<FooFlags>>().unwrap(), A | C);
+        assert_eq!("  D|B ".parse::<BitBag<FooFlags>>().unwrap(), B | D);
+    }
+
+    #[test]
+    fn from_names() {
+        assert_eq!(BitBag::<FooFlags>::from_names(["C", "A"]).unwrap(), A | C);
+        let owned = vec![String::from("D "), String::from("0x2")];
+        assert_eq!(BitBag::<FooFlags>::from_names(&owned).unwrap(), B | D);
+        assert_eq!(
+            BitBag::<FooFlags>::from_names(Vec::<&str>::new()).unwrap(),
+            BitBag::empty()
+        );
+        assert!(matches!(
+            BitBag::<FooFlags>::from_names(["A", ""]),
+            Err(ParseError::EmptyInput)
+        ));
+        assert!(matches!(
+            BitBag::<FooFlags>::from_names(["A | B"]),
+            Err(ParseError::UnknownName(name)) if name == "A | B"
+        ));
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        for bag in [A | B, B | C | D, BitBag::empty(), BitBag::all()] {
+            assert_eq!(bag.to_string().parse::<BitBag<FooFlags>>().unwrap(), bag);
+        }
+    }
+
+    #[test]
+    fn numeric() {
+        assert_eq!("0x3".parse::<BitBag<FooFlags>>().unwrap(), A | B);
+        assert_eq!("12".parse::<BitBag<FooFlags>>().unwrap(), C | D);
+        assert_eq!("0b0001 | D".parse::<BitBag<FooFlags>>().unwrap(), A | D);
+        assert_eq!("0o4".parse::<BitBag<FooFlags>>().unwrap().get(), 0b0100);
+    }
+
+    #[test]
+    fn fail_numeric() {
+        assert!(matches!(
+            "0x10".parse::<BitBag<FooFlags>>(),
+            Err(ParseError::NonFlagBits(e)) if e.unrecognised() == 0b1_0000
+        ));
+        assert!(matches!(
+            "0xZZ".parse::<BitBag<FooFlags>>(),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+        assert!(matches!(
+            "0x-1".parse::<BitBag<FooFlags>>(),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+        assert!(matches!(
+            "256".parse::<BitBag<FooFlags>>(),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+    }
+
+    #[test]
+    fn policy() {
+        let s = "A | E | 0x2 | F";
+        assert!(matches!(
+            BitBag::<FooFlags>::from_str_with(s, UnknownNames::Error),
+            Err(ParseError::UnknownName(name)) if name == "E"
+        ));
+
+        let parsed = BitBag::<FooFlags>::from_str_with(s, UnknownNames::Skip).unwrap();
+        assert_eq!(parsed.bag, A | B);
+        assert!(parsed.unknown.is_empty());
+
+        let parsed = BitBag::<FooFlags>::from_str_with(s, UnknownNames::Collect).unwrap();
+        assert_eq!(parsed.bag, A | B);
+        assert_eq!(parsed.unknown, ["E", "F"]);
+
+        assert!(matches!(
+            BitBag::<FooFlags>::from_str_with("E | 0x10", UnknownNames::Skip),
+            Err(ParseError::NonFlagBits(_))
+        ));
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(
+            BitBag::<FooFlags>::from_hex_str("0xF").unwrap(),
+            BitBag::all()
+        );
+        assert_eq!(BitBag::<FooFlags>::from_hex_str("0X0a").unwrap(), B | D);
+        assert_eq!(BitBag::<FooFlags>::from_hex_str("1").unwrap().get(), 0b0001);
+        assert!(matches!(
+            BitBag::<FooFlags>::from_hex_str("0x1F"),
+            Err(ParseError::NonFlagBits(e)) if e.unrecognised() == 0x10
+        ));
+        assert!(matches!(
+            BitBag::<FooFlags>::from_hex_str("0x"),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+        assert!(matches!(
+            BitBag::<FooFlags>::from_hex_str("G"),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+    }
+
+    #[test]
+    fn hex_signed() {
+        use crate as bitbag;
+
+        #[derive(Debug, BitBaggable)]
+        #[repr(i8)]
+        enum Small {
+            Low = 1,
+            Sign = i8::MIN,
+        }
+        #[derive(Debug, BitBaggable)]
+        #[repr(i32)]
+        enum Large {
+            Low = 1,
+            Sign = i32::MIN,
+        }
+
+        let small = BitBag::<Small>::all();
+        assert_eq!(small.to_hex_string(), "0x81");
+        assert_eq!(BitBag::from_hex_str(&small.to_hex_string()).unwrap(), small);
+        assert_eq!("Low | 0x80".parse::<BitBag<Small>>().unwrap(), small);
+        assert_eq!("0b1000_0001".parse::<BitBag<Small>>().unwrap(), small);
+        assert!(matches!(
+            BitBag::<Small>::from_hex_str("0x181"),
+            Err(ParseError::InvalidNumeric(_))
+        ));
+
+        let large = BitBag::<Large>::all();
+        assert_eq!(large.to_hex_string(), "0x80000001");
+        assert_eq!(BitBag::from_hex_str(&large.to_hex_string()).unwrap(), large);
+        assert_eq!("0x8000_0000 | Low".parse::<BitBag<Large>>().unwrap(), large);
+    }
+
+    #[test]
+    fn fail_name() {
+        assert!(matches!(
+            "A | E".parse::<BitBag<FooFlags>>(),
+            Err(ParseError::UnknownName(name)) if name == "E"
+        ));
+    }
+
+    #[test]
+    fn empty_input() {
+        for s in ["A || B", "A |", "| A"] {
+            assert!(matches!(
+                BitBag::<FooFlags>::from_str_with(s, UnknownNames::Skip),
+                Err(ParseError::EmptyInput)
+            ));
+        }
+        assert!(matches!(
+            BitBag::<FooFlags>::from_hex_str(" "),
+            Err(ParseError::EmptyInput)
+        ));
+        assert!("".parse::<BitBag<FooFlags>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn suggestion() {
+        let suggest = |s: &str| s.parse::<BitBag<FooFlags>>().unwrap_err().suggestion();
+        assert_eq!(suggest("a"), Some("A"));
+        assert_eq!(suggest("E"), None);
+        assert_eq!(suggest("0x10"), None);
+        assert_eq!(edit_distance(&['k', 'i', 't'], &['s', 'i', 't', 's']), 2);
+    }
+
+    #[test]
+    fn variant_index() {
+        use crate as bitbag;
+
+        #[derive(Debug, BitBaggable)]
+        #[repr(u8)]
+        enum Unsorted {
+            Zeta = 0b001,
+            Alpha = 0b010,
+            Mu = 0b100,
+        }
+
+        for (ix, (name, _, _)) in Unsorted::VARIANTS.iter().enumerate() {
+            assert_eq!(Unsorted::variant_index(name), Some(ix));
+        }
+        assert_eq!(Unsorted::variant_index("Beta"), None);
+        assert_eq!(by_name::<Unsorted>("Mu"), Some(0b100));
+    }
+}
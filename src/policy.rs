@@ -0,0 +1,171 @@
+//! Enforcing which flags a bag must, and mustn't, have, see [`BitBag::require_all`] and [`BitBag::forbid`].
+
+use crate::{BitBag, BitBaggable};
+use core::fmt::{self, Debug, Display};
+
+/// Policy
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Check that every flag in `mask` is set, failing with the ones which aren't.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable, BitOr};
+    /// # #[derive(BitBaggable, BitOr)]
+    /// # #[repr(u8)]
+    /// # enum Perm {
+    /// #     Read = 0b0001,
+    /// #     Write = 0b0010,
+    /// #     Admin = 0b0100,
+    /// # }
+    /// let granted = Perm::Read | Perm::Admin;
+    /// granted.require_all(Perm::Read | Perm::Admin).unwrap();
+    ///
+    /// let e = granted.require_all(Perm::Read | Perm::Write).unwrap_err();
+    /// assert_eq!(e.to_string(), "missing required flags: Write");
+    ///
+    /// let e = granted.forbid(Perm::Write | Perm::Admin).unwrap_err();
+    /// assert_eq!(e.to_string(), "forbidden flags are set: Admin");
+    /// ```
+    pub fn require_all(&self, mask: Self) -> Result<(), MissingFlags<PossibleFlagsT>> {
+        let missing = BitBag::new(mask.repr & !self.repr);
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(MissingFlags { missing }),
+        }
+    }
+
+    /// Check that no flag in `mask` is set, failing with the ones which are, see [`BitBag::require_all`]
+    pub fn forbid(&self, mask: Self) -> Result<(), ForbiddenFlags<PossibleFlagsT>> {
+        let forbidden = BitBag::new(mask.repr & self.repr);
+        match forbidden.is_empty() {
+            true => Ok(()),
+            false => Err(ForbiddenFlags { forbidden }),
+        }
+    }
+}
+
+/// The error returned by [`BitBag::require_all`]
+#[non_exhaustive]
+pub struct MissingFlags<PossibleFlagsT: BitBaggable> {
+    missing: BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> MissingFlags<PossibleFlagsT> {
+    /// The required flags which weren't set
+    pub fn missing(&self) -> BitBag<PossibleFlagsT> {
+        self.missing
+    }
+}
+
+/// The error returned by [`BitBag::forbid`]
+#[non_exhaustive]
+pub struct ForbiddenFlags<PossibleFlagsT: BitBaggable> {
+    forbidden: BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> ForbiddenFlags<PossibleFlagsT> {
+    /// The forbidden flags which were set
+    pub fn forbidden(&self) -> BitBag<PossibleFlagsT> {
+        self.forbidden
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for MissingFlags<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for MissingFlags<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> PartialEq for MissingFlags<PossibleFlagsT> {
+    fn eq(&self, other: &Self) -> bool {
+        self.missing == other.missing
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Eq for MissingFlags<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> Debug for MissingFlags<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MissingFlags")
+            .field("missing", &self.missing)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for MissingFlags<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing required flags: {}", self.missing)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for MissingFlags<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Debug
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for ForbiddenFlags<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for ForbiddenFlags<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> PartialEq for ForbiddenFlags<PossibleFlagsT> {
+    fn eq(&self, other: &Self) -> bool {
+        self.forbidden == other.forbidden
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Eq for ForbiddenFlags<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> Debug for ForbiddenFlags<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForbiddenFlags")
+            .field("forbidden", &self.forbidden)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for ForbiddenFlags<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "forbidden flags are set: {}", self.forbidden)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for ForbiddenFlags<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Debug
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    #[test]
+    fn require_all() {
+        let bag = A | B;
+        assert_eq!(bag.require_all(A | B), Ok(()));
+        assert_eq!(bag.require_all(BitBag::empty()), Ok(()));
+        let e = bag.require_all(A | C | D).unwrap_err();
+        assert_eq!(e.missing(), C | D);
+        assert_eq!(e.to_string(), "missing required flags: C | D");
+    }
+
+    #[test]
+    fn forbid() {
+        let bag = A | B;
+        assert_eq!(bag.forbid(C | D), Ok(()));
+        assert_eq!(BitBag::<FooFlags>::empty().forbid(BitBag::all()), Ok(()));
+        let e = bag.forbid(B | C).unwrap_err();
+        assert_eq!(e.forbidden(), BitBag::just(B));
+        assert_eq!(e.to_string(), "forbidden flags are set: B");
+    }
+}
@@ -0,0 +1,104 @@
+//! [postgres-types](https://docs.rs/postgres-types) support for Postgres bit strings, enabled by the `postgres-types` feature.
+//!
+//! A [`BitBag`] is written as a `BIT(n)` or `VARBIT` with one bit per bit of the inner primitive,
+//! most significant first, so `psql` shows the same digits as `{:b}`; a `u16` bag needs a `BIT(16)` column.
+//! Reading accepts bit strings up to that width, right-aligned,
+//! and fails if there are bits set which aren't defined in the enum.
+
+use crate::{BitBag, BitBaggable};
+use ::postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+use bytes::BytesMut;
+use num::{One as _, PrimInt as _, Zero as _};
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt::{Binary, Debug},
+};
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+impl<PossibleFlagsT: BitBaggable> ToSql for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        let mut bytes = [0; 16];
+        let bytes = &mut bytes[..Self::ENCODED_LEN];
+        self.encode_into(bytes)?;
+        bytes.reverse();
+        out.extend_from_slice(&(Self::ENCODED_LEN as i32 * 8).to_be_bytes());
+        out.extend_from_slice(bytes);
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::BIT | Type::VARBIT)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, PossibleFlagsT: BitBaggable> FromSql<'a> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary + Debug + Send + Sync,
+{
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        let (len, bytes) = match raw {
+            [a, b, c, d, bytes @ ..] => (i32::from_be_bytes([*a, *b, *c, *d]), bytes),
+            _ => return Err("bit string is missing its length".into()),
+        };
+        let len = usize::try_from(len).map_err(|_| "bit string has a negative length")?;
+        if bytes.len() != len.div_ceil(8) {
+            return Err("bit string length doesn't match its data".into());
+        }
+        if len > Self::ENCODED_LEN * 8 {
+            return Err(format!(
+                "bit string of {} bits is too wide for {} bits",
+                len,
+                Self::ENCODED_LEN * 8
+            )
+            .into());
+        }
+        let mut repr = PossibleFlagsT::ReprT::zero();
+        for ix in 0..len {
+            if bytes[ix / 8] & (0x80 >> (ix % 8)) != 0 {
+                repr = repr | PossibleFlagsT::ReprT::one().unsigned_shl((len - 1 - ix) as u32);
+            }
+        }
+        Ok(BitBag::new_strict(repr)?)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::BIT | Type::VARBIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::postgres_types::{FromSql, ToSql, Type};
+    use bytes::BytesMut;
+
+    #[test]
+    fn to_sql() {
+        let mut out = BytesMut::new();
+        (A | C).to_sql(&Type::VARBIT, &mut out).unwrap();
+        assert_eq!(&out[..], [0, 0, 0, 8, 0b0000_0101]);
+        assert!(<BitBag<FooFlags> as ToSql>::accepts(&Type::BIT));
+        assert!(!<BitBag<FooFlags> as ToSql>::accepts(&Type::INT4));
+    }
+
+    #[test]
+    fn from_sql() {
+        let bag = BitBag::<FooFlags>::from_sql(&Type::VARBIT, &[0, 0, 0, 8, 0b0000_1001]).unwrap();
+        assert_eq!(bag, A | D);
+        // B'110' is right-aligned
+        let bag = BitBag::<FooFlags>::from_sql(&Type::VARBIT, &[0, 0, 0, 3, 0b1100_0000]).unwrap();
+        assert_eq!(bag, B | C);
+
+        assert!(BitBag::<FooFlags>::from_sql(&Type::VARBIT, &[0, 0, 0, 8, 0b1000_0000]).is_err());
+        assert!(BitBag::<FooFlags>::from_sql(&Type::VARBIT, &[0, 0, 0, 9, 0, 0]).is_err());
+        assert!(BitBag::<FooFlags>::from_sql(&Type::VARBIT, &[0, 0, 0, 8]).is_err());
+    }
+}
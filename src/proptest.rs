@@ -0,0 +1,97 @@
+//! [proptest](https://docs.rs/proptest) support, enabled by the `proptest` feature.
+//!
+//! [`any_bitbag`] generates bags of declared flags only, shrinking by unsetting flags towards the empty bag.
+//! It is also the [`Arbitrary`] strategy, so `any::<BitBag<T>>()` works too.
+//! ```
+//! use bitbag::{BitBag, BitBaggable};
+//! use proptest::prelude::*;
+//!
+//! #[derive(Debug, Clone, BitBaggable)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! proptest! {
+//!     fn only_recognised(bag in bitbag::proptest::any_bitbag::<Flags>()) {
+//!         prop_assert!(!bag.has_unrecognised_bits());
+//!     }
+//! }
+//! only_recognised();
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::proptest::{
+    arbitrary::{any, Arbitrary},
+    bool::Any,
+    collection::{vec, VecStrategy},
+    sample::{select, Select},
+    strategy::{Map, Strategy},
+};
+use std::fmt::Debug;
+
+/// Bags of declared flags, shrinking towards the empty bag
+pub fn any_bitbag<PossibleFlagsT: BitBaggable>() -> impl Strategy<Value = BitBag<PossibleFlagsT>>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    BitBag::arbitrary()
+}
+
+/// Single declared flags, shrinking towards the first variant.
+///
+/// # Panics
+/// If the enum has no variants.
+pub fn any_flag<PossibleFlagsT: BitBaggable + Clone + Debug>() -> Select<PossibleFlagsT> {
+    select(
+        PossibleFlagsT::VARIANTS
+            .iter()
+            .map(|(_, flag, _)| flag.clone())
+            .collect::<Vec<_>>(),
+    )
+}
+
+impl<PossibleFlagsT: BitBaggable> Arbitrary for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    type Parameters = ();
+    type Strategy = Map<VecStrategy<Any>, fn(Vec<bool>) -> Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(any::<bool>(), PossibleFlagsT::VARIANTS.len()).prop_map(|set| {
+            let mut set = set.into_iter();
+            BitBag::from_bools(|| set.next().unwrap_or(false))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{any_bitbag, any_flag};
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+    use ::proptest::{prelude::*, strategy::ValueTree as _, test_runner::TestRunner};
+
+    proptest! {
+        #[test]
+        fn recognised(bag in any_bitbag::<FooFlags>(), flag in any_flag::<FooFlags>()) {
+            prop_assert!(!bag.has_unrecognised_bits());
+            prop_assert!(BitBag::<FooFlags>::all().is_set(flag));
+        }
+
+        #[test]
+        fn arbitrary(bag in any::<BitBag<FooFlags>>()) {
+            prop_assert!(!bag.has_unrecognised_bits());
+        }
+    }
+
+    #[test]
+    fn shrinks_to_empty() {
+        let mut runner = TestRunner::deterministic();
+        let mut tree = any_bitbag::<FooFlags>().new_tree(&mut runner).unwrap();
+        while tree.simplify() {}
+        assert!(tree.current().is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! Helpers for carrying bags in protobuf messages, as generated by [prost](https://docs.rs/prost).
+//!
+//! A bag can either be sent as a `uint32`/`uint64` field holding the bits,
+//! or as a `repeated` enum field listing the set flags.
+//! Enabled by the `protobuf` feature.
+//! ```
+//! # use bitbag::{BitBag, BitBaggable};
+//! # use std::convert::TryFrom;
+//! #[derive(BitBaggable, Debug, Clone, Copy)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! // `prost` generates `TryFrom<i32>` and `From<Proto> for i32` for protobuf enums
+//! #[derive(Clone, Copy)]
+//! enum Proto {
+//!     A = 1,
+//!     B = 2,
+//! }
+//! # impl TryFrom<i32> for Proto {
+//! #     type Error = ();
+//! #     fn try_from(value: i32) -> Result<Self, ()> {
+//! #         match value { 1 => Ok(Proto::A), 2 => Ok(Proto::B), _ => Err(()) }
+//! #     }
+//! # }
+//! # impl From<Proto> for i32 {
+//! #     fn from(proto: Proto) -> i32 { proto as i32 }
+//! # }
+//! impl From<Flags> for Proto {
+//!     // ...
+//! #   fn from(flag: Flags) -> Self { match flag { Flags::A => Proto::A, Flags::B => Proto::B } }
+//! }
+//! impl From<Proto> for Flags {
+//!     // ...
+//! #   fn from(proto: Proto) -> Self { match proto { Proto::A => Flags::A, Proto::B => Flags::B } }
+//! }
+//!
+//! let bag = BitBag::<Flags>::all();
+//! let repeated = bitbag::protobuf::to_repeated::<_, Proto>(bag);
+//! assert_eq!(repeated, [1, 2]);
+//! assert_eq!(bitbag::protobuf::from_repeated::<_, Proto>(&repeated).unwrap(), bag);
+//! assert!(bitbag::protobuf::from_repeated::<Flags, Proto>(&[3]).is_err());
+//! ```
+
+use crate::{repr_from_bits, repr_to_bits, BitBag, BitBaggable, NonFlagBits};
+use alloc::vec::Vec;
+use core::{
+    any::type_name,
+    convert::TryFrom,
+    fmt::{self, Binary, Debug, Display},
+};
+
+/// The bits of `bag`, for a `uint32` field
+///
+/// Signed reprs are reinterpreted bit-for-bit, so an `i32` with bit 31 set is sent as is.
+/// Fails if bits above bit 31 are set, which can only happen for reprs wider than 32 bits.
+pub fn to_u32<PossibleFlagsT: BitBaggable>(
+    bag: BitBag<PossibleFlagsT>,
+) -> Result<u32, ProtoError<PossibleFlagsT>> {
+    to_bits(bag, "uint32")
+}
+
+/// The bits of `bag`, for a `uint64` field
+///
+/// Signed reprs are reinterpreted bit-for-bit, so an `i64` with bit 63 set is sent as is.
+/// Fails if bits above bit 63 are set, which can only happen for reprs wider than 64 bits.
+pub fn to_u64<PossibleFlagsT: BitBaggable>(
+    bag: BitBag<PossibleFlagsT>,
+) -> Result<u64, ProtoError<PossibleFlagsT>> {
+    to_bits(bag, "uint64")
+}
+
+/// Read a bag from a `uint32` field, rejecting bits which aren't defined in the enum.
+pub fn from_u32<PossibleFlagsT: BitBaggable>(
+    value: u32,
+) -> Result<BitBag<PossibleFlagsT>, ProtoError<PossibleFlagsT>> {
+    from_bits(value.into())
+}
+
+/// Read a bag from a `uint64` field, rejecting bits which aren't defined in the enum.
+pub fn from_u64<PossibleFlagsT: BitBaggable>(
+    value: u64,
+) -> Result<BitBag<PossibleFlagsT>, ProtoError<PossibleFlagsT>> {
+    from_bits(value)
+}
+
+fn to_bits<PossibleFlagsT: BitBaggable, FieldT: TryFrom<u128>>(
+    bag: BitBag<PossibleFlagsT>,
+    field: &'static str,
+) -> Result<FieldT, ProtoError<PossibleFlagsT>> {
+    repr_to_bits(bag.get())
+        .and_then(|bits| FieldT::try_from(bits).ok())
+        .ok_or(ProtoError::TooWide { field })
+}
+
+fn from_bits<PossibleFlagsT: BitBaggable>(
+    value: u64,
+) -> Result<BitBag<PossibleFlagsT>, ProtoError<PossibleFlagsT>> {
+    let repr = repr_from_bits(value.into()).ok_or(ProtoError::OutOfRange(value))?;
+    BitBag::new_strict(repr).map_err(ProtoError::NonFlagBits)
+}
+
+/// The set flags of `bag`, as the values of a `repeated` enum field, in [`BitBaggable::ORDER`].
+pub fn to_repeated<PossibleFlagsT, ProtoT>(bag: BitBag<PossibleFlagsT>) -> Vec<i32>
+where
+    PossibleFlagsT: BitBaggable + Clone,
+    ProtoT: From<PossibleFlagsT> + Into<i32>,
+{
+    bag.into_iter()
+        .map(|flag| ProtoT::from(flag).into())
+        .collect()
+}
+
+/// Read a bag from the values of a `repeated` enum field.
+///
+/// Fails on the first value which isn't a variant of `ProtoT`.
+pub fn from_repeated<PossibleFlagsT, ProtoT>(
+    values: &[i32],
+) -> Result<BitBag<PossibleFlagsT>, ProtoError<PossibleFlagsT>>
+where
+    PossibleFlagsT: BitBaggable + From<ProtoT>,
+    ProtoT: TryFrom<i32>,
+{
+    let mut bag = BitBag::empty();
+    for value in values {
+        let proto = ProtoT::try_from(*value).map_err(|_| ProtoError::UnknownValue {
+            value: *value,
+            proto: type_name::<ProtoT>(),
+        })?;
+        bag.set(PossibleFlagsT::from(proto));
+    }
+    Ok(bag)
+}
+
+/// The error returned when reading a [`BitBag`] from, or writing one to, a protobuf field fails
+#[non_exhaustive]
+pub enum ProtoError<PossibleFlagsT: BitBaggable> {
+    /// A `repeated` field contained a value which isn't a variant of the protobuf enum
+    UnknownValue {
+        value: i32,
+        /// The name of the protobuf enum
+        proto: &'static str,
+    },
+    /// An integer field was too wide for the enum's repr
+    OutOfRange(u64),
+    /// A bag had bits set which don't fit in the integer field
+    TooWide {
+        /// The protobuf type of the field, `uint32` or `uint64`
+        field: &'static str,
+    },
+    /// An integer field had bits set which aren't defined in the enum
+    NonFlagBits(NonFlagBits<PossibleFlagsT>),
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for ProtoError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtoError::UnknownValue { value, proto } => f
+                .debug_struct("UnknownValue")
+                .field("value", value)
+                .field("proto", proto)
+                .finish(),
+            ProtoError::OutOfRange(value) => f.debug_tuple("OutOfRange").field(value).finish(),
+            ProtoError::TooWide { field } => {
+                f.debug_struct("TooWide").field("field", field).finish()
+            }
+            ProtoError::NonFlagBits(e) => f.debug_tuple("NonFlagBits").field(e).finish(),
+        }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> core::error::Error for ProtoError<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Binary + Debug
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> Display for ProtoError<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtoError::UnknownValue { value, proto } => {
+                write!(f, "{value} is not a value of the protobuf enum {proto}")
+            }
+            ProtoError::OutOfRange(value) => write!(
+                f,
+                "{value:#x} doesn't fit in the repr of the enum {}",
+                type_name::<PossibleFlagsT>()
+            ),
+            ProtoError::TooWide { field } => write!(
+                f,
+                "the bits of a bag of {} don't fit in a protobuf {field} field",
+                type_name::<PossibleFlagsT>()
+            ),
+            ProtoError::NonFlagBits(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags;
+
+    #[test]
+    fn bits() {
+        let bag = BitBag::<FooFlags>::new(0b1010);
+        assert_eq!(to_u32(bag).unwrap(), 0b1010);
+        assert_eq!(to_u64(bag).unwrap(), 0b1010);
+        assert_eq!(from_u32::<FooFlags>(0b1010).unwrap(), bag);
+        assert_eq!(from_u64::<FooFlags>(0b1010).unwrap(), bag);
+        assert!(matches!(
+            from_u32::<FooFlags>(0b1_0000),
+            Err(ProtoError::NonFlagBits(_))
+        ));
+        assert!(matches!(
+            from_u64::<FooFlags>(0x100),
+            Err(ProtoError::OutOfRange(0x100))
+        ));
+    }
+
+    #[test]
+    fn signed() {
+        use crate as bitbag;
+
+        #[derive(Debug, BitBaggable)]
+        #[repr(i32)]
+        enum Signed {
+            Low = 1,
+            High = i32::MIN,
+        }
+        let bag = BitBag::<Signed>::all();
+        assert_eq!(to_u32(bag).unwrap(), 0x8000_0001);
+        assert_eq!(to_u64(bag).unwrap(), 0x8000_0001);
+        assert_eq!(from_u32::<Signed>(0x8000_0001).unwrap(), bag);
+        assert_eq!(from_u64::<Signed>(0x8000_0001).unwrap(), bag);
+        assert!(matches!(
+            from_u64::<Signed>(0x1_0000_0000),
+            Err(ProtoError::OutOfRange(0x1_0000_0000))
+        ));
+    }
+
+    #[test]
+    fn too_wide() {
+        use crate as bitbag;
+
+        #[derive(Debug, BitBaggable)]
+        #[repr(u64)]
+        enum Wide {
+            Low = 1,
+            High = 1 << 40,
+        }
+        assert_eq!(to_u32(BitBag::<Wide>::just(Wide::Low)).unwrap(), 1);
+        let bag = BitBag::<Wide>::all();
+        assert_eq!(to_u64(bag).unwrap(), 1 << 40 | 1);
+        let e = to_u32(bag).unwrap_err();
+        assert!(matches!(e, ProtoError::TooWide { field: "uint32" }));
+        assert!(e
+            .to_string()
+            .ends_with("don't fit in a protobuf uint32 field"));
+    }
+
+    #[test]
+    fn unknown_value() {
+        struct Proto;
+        impl TryFrom<i32> for Proto {
+            type Error = ();
+            fn try_from(_: i32) -> Result<Self, ()> {
+                Err(())
+            }
+        }
+        impl From<Proto> for FooFlags {
+            fn from(_: Proto) -> Self {
+                FooFlags::A
+            }
+        }
+        let e = from_repeated::<FooFlags, Proto>(&[7]).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("7 is not a value of the protobuf enum"));
+    }
+}
@@ -0,0 +1,176 @@
+//! [PyO3](https://docs.rs/pyo3) support, enabled by the `pyo3` feature.
+//!
+//! A [`BitBag`] converts to a Python `int`, and can be extracted from an `int` (including an `enum.IntFlag`),
+//! or from any iterable of flag names, so extension functions can take flag parameters either way.
+//! Extraction rejects unrecognised bits and names with a `ValueError`, and anything else with a `TypeError`.
+//!
+//! [`to_names`] instead converts to a list of flag names.
+//! ```no_run
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use pyo3::prelude::*;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! #[pyfunction]
+//! fn grant(perms: BitBag<Perm>) -> BitBag<Perm> {
+//!     perms
+//! }
+//!
+//! // `grant(3)`, `grant(Perm.READ | Perm.WRITE)` and `grant(["Read", "Write"])` from Python all return `3`
+//! ```
+
+use crate::{parse::by_name, BitBag, BitBaggable, ParseError};
+use ::pyo3::{
+    conversion::FromPyObjectOwned,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyInt, PyList, PyString},
+    Borrowed,
+};
+use std::fmt::Binary;
+
+const EXPECTED: &str = "expected an int, an IntFlag, or an iterable of flag names";
+
+impl<'a, 'py, PossibleFlagsT> FromPyObject<'a, 'py> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: FromPyObjectOwned<'py> + Binary,
+{
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if obj.is_instance_of::<PyInt>() {
+            let repr = obj.extract::<PossibleFlagsT::ReprT>().map_err(Into::into)?;
+            return BitBag::new_strict(repr).map_err(|e| PyValueError::new_err(e.to_string()));
+        }
+        // strings are iterable, but a single name isn't a list of names
+        if obj.is_instance_of::<PyString>() {
+            return Err(PyTypeError::new_err(EXPECTED));
+        }
+        let names = obj.try_iter().map_err(|_| PyTypeError::new_err(EXPECTED))?;
+        let mut bag = BitBag::empty();
+        for name in names {
+            let name = name?;
+            let name = name
+                .cast::<PyString>()
+                .map_err(|_| PyTypeError::new_err(EXPECTED))?
+                .to_str()?;
+            match by_name::<PossibleFlagsT>(name) {
+                Some(repr) => bag.set_raw(repr),
+                None => {
+                    let e = ParseError::<PossibleFlagsT>::UnknownName(name.to_string());
+                    return Err(PyValueError::new_err(e.to_string()));
+                }
+            };
+        }
+        Ok(bag)
+    }
+}
+
+impl<'py, PossibleFlagsT: BitBaggable> IntoPyObject<'py> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: IntoPyObject<'py>,
+{
+    type Target = <PossibleFlagsT::ReprT as IntoPyObject<'py>>::Target;
+    type Output = <PossibleFlagsT::ReprT as IntoPyObject<'py>>::Output;
+    type Error = <PossibleFlagsT::ReprT as IntoPyObject<'py>>::Error;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.repr.into_pyobject(py)
+    }
+}
+
+/// A list of the names of the flags set in `bag`, leaving out any unrecognised bits.
+pub fn to_names<PossibleFlagsT: BitBaggable>(
+    py: Python<'_>,
+    bag: BitBag<PossibleFlagsT>,
+) -> PyResult<Bound<'_, PyList>> {
+    PyList::new(py, bag.iter_names())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+    use ::pyo3::types::PyDict;
+    use std::ffi::CString;
+
+    fn attach(f: impl for<'py> FnOnce(Python<'py>)) {
+        Python::initialize();
+        Python::attach(f)
+    }
+
+    fn eval<'py>(
+        py: Python<'py>,
+        code: &str,
+        globals: Option<&Bound<'py, PyDict>>,
+    ) -> Bound<'py, PyAny> {
+        let code = CString::new(code).unwrap();
+        py.eval(&code, globals, None).unwrap()
+    }
+
+    #[test]
+    fn extract() {
+        attach(|py| {
+            let bag = eval(py, "5", None).extract::<BitBag<FooFlags>>().unwrap();
+            assert_eq!(bag, A | C);
+            let bag = eval(py, "['B', 'D']", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap();
+            assert_eq!(bag, B | D);
+            let bag = eval(py, "('A',)", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap();
+            assert_eq!(bag, BitBag::just(A));
+        });
+    }
+
+    #[test]
+    fn int_flag() {
+        attach(|py| {
+            let globals = PyDict::new(py);
+            let class = CString::new("import enum\nclass Foo(enum.IntFlag):\n    A = 1\n    B = 2")
+                .unwrap();
+            py.run(&class, Some(&globals), None).unwrap();
+            let flag = eval(py, "Foo.A | Foo.B", Some(&globals));
+            assert_eq!(flag.extract::<BitBag<FooFlags>>().unwrap(), A | B);
+        });
+    }
+
+    #[test]
+    fn errors() {
+        attach(|py| {
+            let e = eval(py, "16", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap_err();
+            assert!(e.is_instance_of::<PyValueError>(py));
+            let e = eval(py, "['A', 'E']", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap_err();
+            assert!(e.is_instance_of::<PyValueError>(py));
+            let e = eval(py, "'A'", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap_err();
+            assert!(e.is_instance_of::<PyTypeError>(py));
+            let e = eval(py, "[1]", None)
+                .extract::<BitBag<FooFlags>>()
+                .unwrap_err();
+            assert!(e.is_instance_of::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn into_python() {
+        attach(|py| {
+            let int = (A | D).into_pyobject(py).unwrap();
+            assert_eq!(int.extract::<u8>().unwrap(), 0b1001);
+            let names = to_names(py, A | D).unwrap();
+            assert_eq!(names.extract::<Vec<String>>().unwrap(), ["A", "D"]);
+        });
+    }
+}
@@ -0,0 +1,44 @@
+//! [quickcheck](https://docs.rs/quickcheck) support, enabled by the `quickcheck` feature.
+//!
+//! Generated bags only contain declared flags, and shrink by unsetting one flag at a time.
+
+use crate::{BitBag, BitBaggable};
+use ::quickcheck::{Arbitrary, Gen};
+
+impl<PossibleFlagsT: BitBaggable> Arbitrary for BitBag<PossibleFlagsT> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        BitBag::from_bools(|| bool::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let bag = *self;
+        Box::new(
+            PossibleFlagsT::VARIANTS
+                .iter()
+                .filter(move |(_, _, repr)| bag.is_set_raw(*repr))
+                .map(move |(_, _, repr)| *bag.clone().unset_raw(*repr)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::quickcheck::{quickcheck, Arbitrary, Gen};
+
+    quickcheck! {
+        fn recognised(bag: BitBag<FooFlags>) -> bool {
+            !bag.has_unrecognised_bits()
+        }
+    }
+
+    #[test]
+    fn shrink() {
+        let shrunk = (A | C).shrink().collect::<Vec<_>>();
+        assert_eq!(shrunk, [BitBag::just(C), BitBag::just(A)]);
+        assert_eq!(BitBag::<FooFlags>::empty().shrink().count(), 0);
+        let mut g = Gen::new(10);
+        assert!(!BitBag::<FooFlags>::arbitrary(&mut g).has_unrecognised_bits());
+    }
+}
@@ -0,0 +1,82 @@
+//! [rand](https://docs.rs/rand) support, enabled by the `rand` feature.
+//!
+//! Sampling a [`BitBag`] from [`StandardUniform`] includes each declared flag with probability one half,
+//! so bags only ever contain recognised bits.
+//! ```
+//! use bitbag::{BitBag, BitBaggable};
+//! use rand::{rngs::StdRng, RngExt as _, SeedableRng as _};
+//!
+//! #[derive(BitBaggable)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! let mut rng = StdRng::seed_from_u64(0);
+//! let bag: BitBag<Flags> = rng.random();
+//! assert!(!bag.has_unrecognised_bits());
+//!
+//! let single = bitbag::rand::random_single::<Flags, _>(&mut rng);
+//! assert_eq!(single.get().count_ones(), 1);
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::rand::{
+    distr::{Distribution, StandardUniform},
+    Rng, RngExt as _,
+};
+
+impl<PossibleFlagsT: BitBaggable> Distribution<BitBag<PossibleFlagsT>> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BitBag<PossibleFlagsT> {
+        random_subset(rng)
+    }
+}
+
+/// A bag with each declared flag set with probability one half
+pub fn random_subset<PossibleFlagsT: BitBaggable, R: Rng + ?Sized>(
+    rng: &mut R,
+) -> BitBag<PossibleFlagsT> {
+    BitBag::from_bools(|| rng.random())
+}
+
+/// A bag with one declared flag set, chosen uniformly, or an empty bag if the enum has no variants
+pub fn random_single<PossibleFlagsT: BitBaggable, R: Rng + ?Sized>(
+    rng: &mut R,
+) -> BitBag<PossibleFlagsT> {
+    let mut bag = BitBag::empty();
+    if !PossibleFlagsT::VARIANTS.is_empty() {
+        let ix = rng.random_range(0..PossibleFlagsT::VARIANTS.len());
+        bag.set_raw(PossibleFlagsT::VARIANTS[ix].2);
+    }
+    bag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_single, random_subset};
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+    use ::rand::{rngs::StdRng, RngExt as _, SeedableRng as _};
+
+    #[test]
+    fn subsets() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut seen = [false; 16];
+        for _ in 0..1000 {
+            let bag: BitBag<FooFlags> = rng.random();
+            seen[bag.get() as usize] = true;
+            assert!(!random_subset::<FooFlags, _>(&mut rng).has_unrecognised_bits());
+        }
+        assert!(seen.iter().all(|seen| *seen));
+    }
+
+    #[test]
+    fn single() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            let bag = random_single::<FooFlags, _>(&mut rng);
+            assert_eq!(bag.get().count_ones(), 1);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! [redis](https://docs.rs/redis) support, enabled by the `redis` feature.
+//!
+//! A [`BitBag`] is stored as an integer, written and read as its inner primitive is.
+//! Reading permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+//! Use [`Strict`] to reject them instead, or [`Masked`] to drop them.
+//! ```no_run
+//! use bitbag::{redis::Strict, BitBag, BitBaggable, BitOr};
+//! use redis::Commands;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u32)]
+//! enum Capability {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! let mut conn = redis::Client::open("redis://127.0.0.1/")?.get_connection()?;
+//! let _: () = conn.set("session:1:caps", Capability::Read | Capability::Write)?;
+//! let Strict(caps): Strict<Capability> = conn.get("session:1:caps")?;
+//! assert_eq!(caps, Capability::Read | Capability::Write);
+//! # Ok::<(), redis::RedisError>(())
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use ::redis::{
+    FromRedisValue, NumericBehavior, ParsingError, RedisWrite, ToRedisArgs, ToSingleRedisArg, Value,
+};
+use std::fmt::{self, Binary, Debug};
+
+impl<PossibleFlagsT: BitBaggable> ToRedisArgs for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: ToRedisArgs,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.repr.write_redis_args(out)
+    }
+
+    fn describe_numeric_behavior(&self) -> NumericBehavior {
+        self.repr.describe_numeric_behavior()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> ToSingleRedisArg for BitBag<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: ToSingleRedisArg
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> FromRedisValue for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: FromRedisValue,
+{
+    fn from_redis_value_ref(v: &Value) -> Result<Self, ParsingError> {
+        PossibleFlagsT::ReprT::from_redis_value_ref(v).map(BitBag::new)
+    }
+
+    fn from_redis_value(v: Value) -> Result<Self, ParsingError> {
+        PossibleFlagsT::ReprT::from_redis_value(v).map(BitBag::new)
+    }
+}
+
+/// Stored like a [`BitBag`], but reading fails if there are bits set which aren't defined in the enum.
+pub struct Strict<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+/// Stored like a [`BitBag`], but reading clears any bits set which aren't defined in the enum, as
+/// [`BitBag::new_masked`] does.
+pub struct Masked<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+macro_rules! impl_wrapper {
+    ($wrapper:ident, $decode:ident) => {
+        impl<PossibleFlagsT: BitBaggable> Debug for $wrapper<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($wrapper)).field(&self.0).finish()
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable> ToRedisArgs for $wrapper<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: ToRedisArgs,
+        {
+            fn write_redis_args<W>(&self, out: &mut W)
+            where
+                W: ?Sized + RedisWrite,
+            {
+                self.0.write_redis_args(out)
+            }
+
+            fn describe_numeric_behavior(&self) -> NumericBehavior {
+                self.0.describe_numeric_behavior()
+            }
+        }
+
+        impl<PossibleFlagsT: BitBaggable> ToSingleRedisArg for $wrapper<PossibleFlagsT> where
+            PossibleFlagsT::ReprT: ToSingleRedisArg
+        {
+        }
+
+        impl<PossibleFlagsT: BitBaggable> FromRedisValue for $wrapper<PossibleFlagsT>
+        where
+            PossibleFlagsT::ReprT: FromRedisValue + Binary,
+        {
+            fn from_redis_value_ref(v: &Value) -> Result<Self, ParsingError> {
+                $decode(PossibleFlagsT::ReprT::from_redis_value_ref(v)?).map($wrapper)
+            }
+
+            fn from_redis_value(v: Value) -> Result<Self, ParsingError> {
+                $decode(PossibleFlagsT::ReprT::from_redis_value(v)?).map($wrapper)
+            }
+        }
+    };
+}
+
+impl_wrapper!(Strict, strict);
+impl_wrapper!(Masked, masked);
+
+fn strict<PossibleFlagsT: BitBaggable>(
+    repr: PossibleFlagsT::ReprT,
+) -> Result<BitBag<PossibleFlagsT>, ParsingError>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    BitBag::new_strict(repr).map_err(|e| e.to_string().into())
+}
+
+fn masked<PossibleFlagsT: BitBaggable>(
+    repr: PossibleFlagsT::ReprT,
+) -> Result<BitBag<PossibleFlagsT>, ParsingError> {
+    Ok(BitBag::new_masked(repr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Masked, Strict};
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::redis::{FromRedisValue, ToRedisArgs, Value};
+
+    #[test]
+    fn to_args() {
+        assert_eq!((A | C).to_redis_args(), [b"5".to_vec()]);
+        assert_eq!(Strict(A | C).to_redis_args(), [b"5".to_vec()]);
+    }
+
+    #[test]
+    fn from_value() {
+        let bag = BitBag::<FooFlags>::from_redis_value(Value::Int(5)).unwrap();
+        assert_eq!(bag, A | C);
+        let bag = BitBag::<FooFlags>::from_redis_value(Value::BulkString(b"5".to_vec())).unwrap();
+        assert_eq!(bag, A | C);
+        let bag = BitBag::<FooFlags>::from_redis_value_ref(&Value::Int(0b10001)).unwrap();
+        assert_eq!(bag.get(), 0b10001);
+        assert!(BitBag::<FooFlags>::from_redis_value(Value::BulkString(b"A".to_vec())).is_err());
+    }
+
+    #[test]
+    fn strict() {
+        let Strict(bag) = Strict::<FooFlags>::from_redis_value(Value::Int(5)).unwrap();
+        assert_eq!(bag, A | C);
+        assert!(Strict::<FooFlags>::from_redis_value(Value::Int(0b10001)).is_err());
+        assert!(Strict::<FooFlags>::from_redis_value_ref(&Value::Int(0b10001)).is_err());
+    }
+
+    #[test]
+    fn masked() {
+        let Masked(bag) = Masked::<FooFlags>::from_redis_value(Value::Int(0b10001)).unwrap();
+        assert_eq!(bag.get(), 0b1);
+    }
+}
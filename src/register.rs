@@ -0,0 +1,117 @@
+//! A common interface to flag registers, see [`FlagRegister`].
+
+use crate::{BitBag, BitBaggable, VolatileBitBag};
+use core::cell::Cell;
+use num::PrimInt;
+
+/// Something holding the bits of a register, which can be read and written through a shared reference.
+///
+/// HAL APIs can accept `&impl FlagRegister<ReprT = u32>` to work with any register backend.
+/// This is implemented for [`VolatileBitBag`], [`Cell`],
+/// and tock-registers' `ReadWrite` and `InMemoryRegister` with the `tock-registers` feature.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, BitOr, FlagRegister};
+/// # use std::cell::Cell;
+/// #[derive(Debug, BitBaggable, BitOr)]
+/// #[repr(u32)]
+/// enum Irq {
+///     Rx = 0b01,
+///     Tx = 0b10,
+/// }
+///
+/// fn enable(register: &impl FlagRegister<ReprT = u32>, irqs: BitBag<Irq>) {
+///     register.modify_flags::<Irq>(|bag| bag.set_raw(irqs.get()));
+/// }
+///
+/// let register = Cell::new(0b1000);
+/// enable(&register, Irq::Rx | Irq::Tx);
+/// assert_eq!(register.get(), 0b1011);
+/// ```
+pub trait FlagRegister {
+    type ReprT: PrimInt;
+
+    fn read_repr(&self) -> Self::ReprT;
+
+    fn write_repr(&self, repr: Self::ReprT);
+
+    /// Read the register once, apply `f`, and write it back once
+    fn modify_repr(&self, f: impl FnOnce(Self::ReprT) -> Self::ReprT) {
+        self.write_repr(f(self.read_repr()))
+    }
+
+    /// Read the register as a bag, permitting (and preserving) unrecognised bits, as [`BitBag::new`] does
+    fn read_flags<PossibleFlagsT>(&self) -> BitBag<PossibleFlagsT>
+    where
+        PossibleFlagsT: BitBaggable<ReprT = Self::ReprT>,
+    {
+        BitBag::new(self.read_repr())
+    }
+
+    fn write_flags<PossibleFlagsT>(&self, bag: BitBag<PossibleFlagsT>)
+    where
+        PossibleFlagsT: BitBaggable<ReprT = Self::ReprT>,
+    {
+        self.write_repr(bag.repr)
+    }
+
+    /// Read the register once, apply `f`, and write it back once,
+    /// keeping the bits which aren't defined in the enum as they were read, as [`BitBag::modify`] does
+    fn modify_flags<PossibleFlagsT>(
+        &self,
+        f: impl FnOnce(&mut BitBag<PossibleFlagsT>) -> &mut BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT: BitBaggable<ReprT = Self::ReprT>,
+    {
+        self.modify_repr(|repr| BitBag::<PossibleFlagsT>::new(repr).modify(f).repr)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> FlagRegister for VolatileBitBag<PossibleFlagsT> {
+    type ReprT = PossibleFlagsT::ReprT;
+
+    fn read_repr(&self) -> Self::ReprT {
+        self.read().repr
+    }
+
+    fn write_repr(&self, repr: Self::ReprT) {
+        self.write(BitBag::new(repr))
+    }
+}
+
+impl<ReprT: PrimInt> FlagRegister for Cell<ReprT> {
+    type ReprT = ReprT;
+
+    fn read_repr(&self) -> ReprT {
+        self.get()
+    }
+
+    fn write_repr(&self, repr: ReprT) {
+        self.set(repr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlagRegister;
+    use crate::tests::FooFlags::{self, *};
+    use crate::{BitBag, VolatileBitBag};
+
+    fn enable(register: &impl FlagRegister<ReprT = u8>) {
+        register.modify_flags::<FooFlags>(|bag| bag.clear_all().set(A).set(C));
+    }
+
+    #[test]
+    fn backends() {
+        let mut raw = 0b1000_0010u8;
+        let volatile = unsafe { VolatileBitBag::<FooFlags>::new(&mut raw) };
+        enable(&volatile);
+        assert_eq!(volatile.read_flags(), BitBag::<FooFlags>::new(0b1000_0101));
+
+        let cell = std::cell::Cell::new(0b0100_0000u8);
+        enable(&cell);
+        cell.modify_repr(|repr| repr | 0b1000);
+        assert_eq!(cell.read_flags::<FooFlags>().get(), 0b0100_1101);
+        cell.write_flags(B | D);
+        assert_eq!(cell.get(), 0b1010);
+    }
+}
@@ -0,0 +1,127 @@
+//! A registry of every [`BitBaggable`] enum in the binary, enabled by the `inventory` feature.
+//!
+//! Register an enum with [`register_flags!`](crate::register_flags), from any crate,
+//! and generic tooling like debug UIs can then enumerate and render them all with [`flag_types`].
+//! ```
+//! use bitbag::BitBaggable;
+//!
+//! #[derive(BitBaggable)]
+//! #[repr(u8)]
+//! enum Permissions {
+//!     Read = 0b01,
+//!     Write = 0b10,
+//! }
+//!
+//! bitbag::register_flags!(Permissions);
+//!
+//! let permissions = bitbag::registry::flag_types()
+//!     .find(|ty| ty.type_name().ends_with("Permissions"))
+//!     .unwrap();
+//! assert_eq!(permissions.names(), ["Read", "Write"]);
+//! assert_eq!(permissions.render(0b11).unwrap(), "Read | Write");
+//! ```
+
+use crate::{iter::variants_in, repr_from_bits, BitBag, BitBaggable};
+#[doc(hidden)]
+pub use ::inventory;
+use std::any::type_name;
+
+/// A registered enum, with its type erased.
+pub struct FlagType {
+    type_name: fn() -> &'static str,
+    names: fn() -> Vec<&'static str>,
+    render: fn(u128) -> Option<String>,
+}
+
+impl FlagType {
+    pub const fn new<PossibleFlagsT: BitBaggable>() -> Self {
+        Self {
+            type_name: type_name::<PossibleFlagsT>,
+            names: names::<PossibleFlagsT>,
+            render: render::<PossibleFlagsT>,
+        }
+    }
+
+    /// The full path of the enum
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// The names of the flags, in [`BitBaggable::ORDER`]
+    pub fn names(&self) -> Vec<&'static str> {
+        (self.names)()
+    }
+
+    /// Format `bits` as a bag, like [`BitBag`]'s [`Display`](std::fmt::Display) implementation.
+    ///
+    /// Signed reprs are read bit-for-bit, so `0x80` is bit 7 of an `i8`.
+    /// Returns [`None`] if `bits` doesn't fit in the enum's repr.
+    pub fn render(&self, bits: u128) -> Option<String> {
+        (self.render)(bits)
+    }
+}
+
+fn names<PossibleFlagsT: BitBaggable>() -> Vec<&'static str> {
+    variants_in::<PossibleFlagsT>(PossibleFlagsT::ORDER)
+        .map(|(name, _, _)| *name)
+        .collect()
+}
+
+fn render<PossibleFlagsT: BitBaggable>(bits: u128) -> Option<String> {
+    let repr = repr_from_bits(bits)?;
+    Some(BitBag::<PossibleFlagsT>::new(repr).to_string())
+}
+
+::inventory::collect!(FlagType);
+
+/// Every enum registered with [`register_flags!`](crate::register_flags), in no particular order
+pub fn flag_types() -> impl Iterator<Item = &'static FlagType> {
+    ::inventory::iter::<FlagType>.into_iter()
+}
+
+/// Add [`BitBaggable`](crate::BitBaggable) enums to the [`registry`](crate::registry).
+#[macro_export]
+macro_rules! register_flags {
+    ($($flags:ty),* $(,)?) => {$(
+        $crate::registry::inventory::submit! {
+            $crate::registry::FlagType::new::<$flags>()
+        }
+    )*};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flag_types;
+    use crate::{self as bitbag, tests::FooFlags, BitBaggable};
+
+    crate::register_flags!(FooFlags);
+
+    #[test]
+    fn registered() {
+        let foo = flag_types()
+            .find(|ty| ty.type_name() == std::any::type_name::<FooFlags>())
+            .unwrap();
+        assert_eq!(foo.names(), ["A", "B", "C", "D"]);
+        assert_eq!(foo.render(0b1001).unwrap(), "A | D");
+        assert_eq!(foo.render(0).unwrap(), "<unset>");
+        assert!(foo.render(0x100).is_none());
+    }
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(i8)]
+    enum Signed {
+        Low = 1,
+        Sign = i8::MIN,
+    }
+
+    crate::register_flags!(Signed);
+
+    #[test]
+    fn signed() {
+        let signed = flag_types()
+            .find(|ty| ty.type_name() == std::any::type_name::<Signed>())
+            .unwrap();
+        assert_eq!(signed.render(0x81).unwrap(), "Low | Sign");
+        assert!(signed.render(0x181).is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! [rkyv](https://docs.rs/rkyv) support, enabled by the `rkyv` feature.
+//!
+//! A [`BitBag`] is archived as its inner primitive, wrapped in an [`ArchivedBitBag`].
+//! Validation fails if there are bits set which aren't defined in the enum,
+//! so a checked archive only ever contains recognised flags.
+
+use crate::{BitBag, BitBaggable};
+use ::rkyv::{
+    bytecheck::CheckBytes,
+    place::Place,
+    rancor::{Fallible, Infallible, ResultExt as _, Source, Strategy},
+    Archive, Archived, Deserialize, Portable, Serialize,
+};
+use core::{
+    fmt::{self, Binary, Debug},
+    marker::PhantomData,
+};
+
+type ArchivedRepr<PossibleFlagsT> = Archived<<PossibleFlagsT as BitBaggable>::ReprT>;
+
+/// The archived form of a [`BitBag`]
+#[repr(transparent)]
+pub struct ArchivedBitBag<PossibleFlagsT: BitBaggable>
+where
+    PossibleFlagsT::ReprT: Archive,
+{
+    repr: ArchivedRepr<PossibleFlagsT>,
+    // `fn` so `ArchivedBitBag` is always `Send` and `Sync`
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+// SAFETY: `ArchivedBitBag` is `repr(transparent)` over a `Portable` primitive.
+unsafe impl<PossibleFlagsT: BitBaggable> Portable for ArchivedBitBag<PossibleFlagsT> where
+    PossibleFlagsT::ReprT: Archive
+{
+}
+
+impl<PossibleFlagsT: BitBaggable> ArchivedBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Archive,
+    ArchivedRepr<PossibleFlagsT>: Deserialize<PossibleFlagsT::ReprT, Strategy<(), Infallible>>,
+{
+    /// Read the bag out of the archive
+    pub fn get(&self) -> BitBag<PossibleFlagsT> {
+        let repr = self
+            .repr
+            .deserialize(Strategy::<_, Infallible>::wrap(&mut ()))
+            .always_ok();
+        BitBag::new(repr)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for ArchivedBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Archive,
+    ArchivedRepr<PossibleFlagsT>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedBitBag")
+            .field("repr", &self.repr)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Archive for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Archive,
+{
+    type Archived = ArchivedBitBag<PossibleFlagsT>;
+    type Resolver = <PossibleFlagsT::ReprT as Archive>::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: `ArchivedBitBag` is `repr(transparent)` over the archived primitive.
+        let out = unsafe { out.cast_unchecked::<ArchivedRepr<PossibleFlagsT>>() };
+        self.repr.resolve(resolver, out)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable, S: Fallible + ?Sized> Serialize<S> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Serialize<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        self.repr.serialize(serializer)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable, D: Fallible + ?Sized> Deserialize<BitBag<PossibleFlagsT>, D>
+    for ArchivedBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Archive,
+    ArchivedRepr<PossibleFlagsT>: Deserialize<PossibleFlagsT::ReprT, D>,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<BitBag<PossibleFlagsT>, D::Error> {
+        self.repr.deserialize(deserializer).map(BitBag::new)
+    }
+}
+
+// SAFETY: the primitive is checked first, and `check_bytes` only returns `Ok` if it is a valid bag.
+unsafe impl<PossibleFlagsT: BitBaggable, C> CheckBytes<C> for ArchivedBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Archive + Binary + Debug + Send + Sync,
+    ArchivedRepr<PossibleFlagsT>:
+        CheckBytes<C> + Deserialize<PossibleFlagsT::ReprT, Strategy<(), Infallible>>,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        // SAFETY: `ArchivedBitBag` is `repr(transparent)` over the archived primitive.
+        unsafe { ArchivedRepr::<PossibleFlagsT>::check_bytes(value.cast(), context)? };
+        // SAFETY: the primitive was just checked.
+        let bag = unsafe { &*value }.get();
+        BitBag::<PossibleFlagsT>::new_strict(bag.get()).into_error()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+    use ::rkyv::rancor::Error;
+
+    #[derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize, Debug, PartialEq)]
+    struct Header {
+        version: u8,
+        flags: BitBag<FooFlags>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let header = Header {
+            version: 1,
+            flags: BitBag::new(0b11),
+        };
+        let bytes = ::rkyv::to_bytes::<Error>(&header).unwrap();
+        let archived = ::rkyv::access::<ArchivedHeader, Error>(&bytes).unwrap();
+        assert_eq!(archived.flags.get(), header.flags);
+        assert_eq!(
+            ::rkyv::deserialize::<Header, Error>(archived).unwrap(),
+            header
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let bytes = ::rkyv::to_bytes::<Error>(&BitBag::<FooFlags>::new(0b1000_0000)).unwrap();
+        assert!(::rkyv::access::<super::ArchivedBitBag<FooFlags>, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+//! [rusqlite](https://docs.rs/rusqlite) support, enabled by the `rusqlite` feature.
+//!
+//! A [`BitBag`] is stored as an `INTEGER`, converted as its inner primitive is.
+//! Reading fails if there are bits set which aren't defined in the enum.
+
+use crate::{BitBag, BitBaggable};
+use ::rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use std::fmt::{Binary, Debug};
+
+impl<PossibleFlagsT: BitBaggable> ToSql for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: ToSql,
+{
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        self.repr.to_sql()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> FromSql for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: FromSql + Binary + Debug + Send + Sync,
+{
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let repr = PossibleFlagsT::ReprT::column_result(value)?;
+        BitBag::new_strict(repr).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::rusqlite::Connection;
+
+    #[test]
+    fn roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        let bag: BitBag<FooFlags> = conn
+            .query_row("SELECT ?1", [A | D], |row| row.get(0))
+            .unwrap();
+        assert_eq!(bag, A | D);
+    }
+
+    #[test]
+    fn strict() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(conn
+            .query_row("SELECT 16", [], |row| row.get::<_, BitBag<FooFlags>>(0))
+            .is_err());
+        assert!(conn
+            .query_row("SELECT 256", [], |row| row.get::<_, BitBag<FooFlags>>(0))
+            .is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Conversions between the [`os`](crate::os) enums and [rustix](https://docs.rs/rustix)'s flag types, enabled by the `rustix` feature.
+//!
+//! rustix uses `u32` where libc uses `c_int`, so the bits are reinterpreted, not range checked.
+
+use crate::{
+    os::{MapFlags, MsgFlags, OpenFlags, ProtFlags},
+    BitBag, NonFlagBits,
+};
+use core::convert::TryFrom;
+
+macro_rules! impl_conversions {
+    ($($flags:ty => $rustix:ty),* $(,)?) => {$(
+        impl From<BitBag<$flags>> for $rustix {
+            fn from(bag: BitBag<$flags>) -> Self {
+                <$rustix>::from_bits_retain(bag.get() as u32)
+            }
+        }
+
+        impl TryFrom<$rustix> for BitBag<$flags> {
+            type Error = NonFlagBits<$flags>;
+
+            /// Fails if `flags` has bits which aren't defined in the enum
+            fn try_from(flags: $rustix) -> Result<Self, Self::Error> {
+                BitBag::new_strict(flags.bits() as i32)
+            }
+        }
+    )*};
+}
+
+impl_conversions!(
+    OpenFlags => ::rustix::fs::OFlags,
+    ProtFlags => ::rustix::mm::ProtFlags,
+    MapFlags => ::rustix::mm::MapFlags,
+    MsgFlags => ::rustix::net::SendFlags,
+    MsgFlags => ::rustix::net::RecvFlags,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rustix::{fs::OFlags, mm, net::RecvFlags};
+
+    #[test]
+    fn roundtrip() {
+        let flags = OFlags::from(OpenFlags::Create | OpenFlags::WriteOnly);
+        assert_eq!(flags, OFlags::CREATE | OFlags::WRONLY);
+        assert_eq!(
+            BitBag::try_from(flags).unwrap(),
+            OpenFlags::Create | OpenFlags::WriteOnly
+        );
+        assert_eq!(
+            mm::ProtFlags::from(ProtFlags::Read | ProtFlags::Exec),
+            mm::ProtFlags::READ | mm::ProtFlags::EXEC
+        );
+        assert_eq!(
+            BitBag::try_from(RecvFlags::PEEK | RecvFlags::WAITALL).unwrap(),
+            MsgFlags::Peek | MsgFlags::WaitAll
+        );
+    }
+
+    #[test]
+    fn unrecognised() {
+        assert!(BitBag::<MapFlags>::try_from(mm::MapFlags::from_bits_retain(1 << 30)).is_err());
+    }
+}
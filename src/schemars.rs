@@ -0,0 +1,61 @@
+//! [schemars](https://docs.rs/schemars) support, enabled by the `schemars` feature.
+//!
+//! The schema matches the human-readable serde representation: an array of unique flag names,
+//! or an integer if [`BitBaggable::ENCODING`] is [`Encoding::Bits`].
+//! The schema is named after the full path of the enum, like `BitBag_my_crate_Flags`.
+
+use crate::{schema_name, BitBag, BitBaggable, Encoding};
+use ::schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use std::{any::type_name, borrow::Cow};
+
+impl<PossibleFlagsT: BitBaggable> JsonSchema for BitBag<PossibleFlagsT> {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Owned(schema_name::<PossibleFlagsT>())
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Owned(format!("bitbag::BitBag<{}>", type_name::<PossibleFlagsT>()))
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        if let Encoding::Bits = PossibleFlagsT::ENCODING {
+            return json_schema!({ "type": "integer" });
+        }
+        let names = PossibleFlagsT::VARIANTS
+            .iter()
+            .map(|(name, _, _)| *name)
+            .collect::<Vec<_>>();
+        json_schema!({
+            "type": "array",
+            "items": {
+                "type": "string",
+                "enum": names,
+            },
+            "uniqueItems": true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+
+    #[test]
+    fn schema() {
+        let schema = ::schemars::schema_for!(BitBag<FooFlags>);
+        assert_eq!(
+            schema.as_value(),
+            &serde_json::json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "BitBag_bitbag_tests_FooFlags",
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "enum": ["A", "B", "C", "D"],
+                },
+                "uniqueItems": true,
+            })
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Setting a flag for the length of a scope, see [`BitBag::scoped_set`].
+
+use crate::{BitBag, BitBaggable};
+use core::{
+    fmt::{self, Debug},
+    ops::{Deref, DerefMut},
+};
+
+impl<PossibleFlagsT: BitBaggable> BitBag<PossibleFlagsT> {
+    /// Set `flag` until the returned guard is dropped, when its bits go back to how they were.
+    ///
+    /// The bag can still be used through the guard, and other changes made meanwhile are kept.
+    /// ```
+    /// # use bitbag::{BitBag, BitBaggable};
+    /// # #[derive(BitBaggable)]
+    /// # #[repr(u8)]
+    /// # enum Logging {
+    /// #     Trace = 0b01,
+    /// #     Timing = 0b10,
+    /// # }
+    /// let mut debug = BitBag::<Logging>::empty();
+    /// {
+    ///     let mut debug = debug.scoped_set(Logging::Trace);
+    ///     assert!(debug.is_set(Logging::Trace));
+    ///     debug.set(Logging::Timing);
+    /// }
+    /// assert!(!debug.is_set(Logging::Trace));
+    /// assert!(debug.is_set(Logging::Timing));
+    /// ```
+    pub fn scoped_set(&mut self, flag: PossibleFlagsT) -> ScopedFlag<'_, PossibleFlagsT> {
+        let bits = flag.into_repr();
+        let was = self.repr & bits;
+        self.set_raw(bits);
+        ScopedFlag {
+            bag: self,
+            bits,
+            was,
+        }
+    }
+}
+
+/// The guard returned by [`BitBag::scoped_set`]
+#[must_use = "the flag is restored as soon as the guard is dropped"]
+pub struct ScopedFlag<'bag, PossibleFlagsT: BitBaggable> {
+    bag: &'bag mut BitBag<PossibleFlagsT>,
+    /// The flag's bits
+    bits: PossibleFlagsT::ReprT,
+    /// Which of them were set beforehand
+    was: PossibleFlagsT::ReprT,
+}
+
+impl<PossibleFlagsT: BitBaggable> Drop for ScopedFlag<'_, PossibleFlagsT> {
+    fn drop(&mut self) {
+        self.bag.repr = (self.bag.repr & !self.bits) | self.was;
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Deref for ScopedFlag<'_, PossibleFlagsT> {
+    type Target = BitBag<PossibleFlagsT>;
+
+    fn deref(&self) -> &Self::Target {
+        self.bag
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> DerefMut for ScopedFlag<'_, PossibleFlagsT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.bag
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for ScopedFlag<'_, PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedFlag")
+            .field("bag", &self.bag)
+            .field("bits", &self.bits)
+            .field("was", &self.was)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags::*;
+    use crate::BitBag;
+
+    #[test]
+    fn restores() {
+        let mut bag = A | B;
+        {
+            let mut guard = bag.scoped_set(C);
+            assert_eq!(*guard, A | B | C);
+            guard.unset(A).set(D);
+        }
+        assert_eq!(bag, B | D);
+    }
+
+    #[test]
+    fn already_set() {
+        let mut bag = BitBag::just(A);
+        {
+            let mut guard = bag.scoped_set(A);
+            guard.unset(A);
+        }
+        assert_eq!(bag, BitBag::just(A));
+    }
+
+    #[test]
+    fn nested() {
+        let mut bag = BitBag::just(A);
+        {
+            let mut outer = bag.scoped_set(B);
+            {
+                let inner = outer.scoped_set(C);
+                assert_eq!(*inner, A | B | C);
+            }
+            assert_eq!(*outer, A | B);
+        }
+        assert_eq!(bag, BitBag::just(A));
+    }
+}
@@ -0,0 +1,762 @@
+//! [serde](https://docs.rs/serde) support, enabled by the `serde` feature.
+//!
+//! For human-readable formats like JSON, a [`BitBag`] is serialized as a list of flag names, which is self-describing.
+//! For binary formats, it is serialized as its inner primitive, which is compact.
+//! Human-readable deserialization also accepts the inner primitive.
+//! An enum can pick one representation for all formats with [`BitBaggable::ENCODING`].
+//!
+//! Deserialization rejects bits and names which aren't defined in the enum, as [`deserialize_strict`] does.
+//! Use [`deserialize_lossy`] or [`Lossy`] to discard them instead.
+//! ```
+//! # use bitbag::{BitBag, BitBaggable};
+//! # #[derive(BitBaggable, Debug)]
+//! # #[repr(u8)]
+//! # enum Flags {
+//! #     A = 0b0001,
+//! #     B = 0b0010,
+//! # }
+//! let bag = BitBag::<Flags>::new(0b0011);
+//! assert_eq!(serde_json::to_string(&bag).unwrap(), r#"["A","B"]"#);
+//! assert_eq!(serde_json::from_str::<BitBag<Flags>>(r#"["A","B"]"#).unwrap(), bag);
+//! assert_eq!(serde_json::from_str::<BitBag<Flags>>("3").unwrap(), bag);
+//!
+//! serde_json::from_str::<BitBag<Flags>>(r#"["C"]"#).unwrap_err();
+//! serde_json::from_str::<BitBag<Flags>>("4").unwrap_err();
+//! ```
+
+use crate::{
+    iter::variants_in, parse::by_name, BitBag, BitBaggable, Encoding, NonFlagBits, ParseError,
+    WideBitBag, WideBitBaggable,
+};
+use ::serde::{
+    de::{self, SeqAccess},
+    ser, Deserialize, Deserializer, Serialize, Serializer,
+};
+use alloc::string::String;
+use core::{fmt, marker::PhantomData};
+
+impl<PossibleFlagsT: BitBaggable> Serialize for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Serialize + fmt::Binary,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (PossibleFlagsT::ENCODING, serializer.is_human_readable()) {
+            (Encoding::Auto, true) | (Encoding::Names, _) => as_names::serialize(self, serializer),
+            (Encoding::Auto, false) | (Encoding::Bits, _) => as_bits::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de, PossibleFlagsT: BitBaggable> Deserialize<'de> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(deserializer, false)
+    }
+}
+
+/// Deserialize, rejecting any bits or names which aren't defined in the enum.
+///
+/// This is what the [`Deserialize`] implementation does, but spelled out, for use with
+/// `#[serde(deserialize_with = "bitbag::serde::deserialize_strict")]`.
+pub fn deserialize_strict<'de, D, PossibleFlagsT>(
+    deserializer: D,
+) -> Result<BitBag<PossibleFlagsT>, D::Error>
+where
+    D: Deserializer<'de>,
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+{
+    deserialize_with(deserializer, false)
+}
+
+/// Deserialize, discarding any bits or names which aren't defined in the enum.
+///
+/// For use with `#[serde(deserialize_with = "bitbag::serde::deserialize_lossy")]`.
+pub fn deserialize_lossy<'de, D, PossibleFlagsT>(
+    deserializer: D,
+) -> Result<BitBag<PossibleFlagsT>, D::Error>
+where
+    D: Deserializer<'de>,
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+{
+    deserialize_with(deserializer, true)
+}
+
+/// Deserializes like [`deserialize_lossy`], for where `deserialize_with` can't reach, like `Vec<Lossy<T>>`.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable};
+/// # #[derive(BitBaggable, Debug)]
+/// # #[repr(u8)]
+/// # enum Flags {
+/// #     A = 0b0001,
+/// # }
+/// use bitbag::serde::Lossy;
+///
+/// let bags = serde_json::from_str::<Vec<Lossy<Flags>>>(r#"[["A", "Z"], 255]"#).unwrap();
+/// assert!(bags.iter().all(|Lossy(bag)| bag.get() == 0b0001));
+/// ```
+pub struct Lossy<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+impl<'de, PossibleFlagsT: BitBaggable> Deserialize<'de> for Lossy<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_lossy(deserializer).map(Lossy)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Serialize for Lossy<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Serialize + fmt::Binary,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+fn deserialize_with<'de, D, PossibleFlagsT>(
+    deserializer: D,
+    lossy: bool,
+) -> Result<BitBag<PossibleFlagsT>, D::Error>
+where
+    D: Deserializer<'de>,
+    PossibleFlagsT: BitBaggable,
+    PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+{
+    match (
+        PossibleFlagsT::ENCODING,
+        deserializer.is_human_readable(),
+        lossy,
+    ) {
+        (Encoding::Auto, true, _) => deserializer.deserialize_any(Visitor {
+            lossy,
+            _flags: PhantomData,
+        }),
+        (Encoding::Names, _, false) => as_names::deserialize(deserializer),
+        (Encoding::Names, _, true) => as_names::deserialize_lossy(deserializer),
+        (Encoding::Auto, false, false) | (Encoding::Bits, _, false) => {
+            as_bits::deserialize(deserializer)
+        }
+        (Encoding::Auto, false, true) | (Encoding::Bits, _, true) => {
+            as_bits::deserialize_lossy(deserializer)
+        }
+    }
+}
+
+fn from_repr<E: de::Error, PossibleFlagsT: BitBaggable>(
+    repr: PossibleFlagsT::ReprT,
+    lossy: bool,
+) -> Result<BitBag<PossibleFlagsT>, E>
+where
+    PossibleFlagsT::ReprT: fmt::Binary,
+{
+    match lossy {
+        true => Ok(BitBag::new_masked(repr)),
+        false => BitBag::new_strict(repr).map_err(de::Error::custom),
+    }
+}
+
+/// Accepts a list of names, or the inner primitive
+struct Visitor<PossibleFlagsT> {
+    lossy: bool,
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Visitor<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: fmt::Binary,
+{
+    fn visit_int<E: de::Error, IntT: num::NumCast + fmt::Display + Copy>(
+        self,
+        v: IntT,
+    ) -> Result<BitBag<PossibleFlagsT>, E> {
+        match num::cast::<IntT, PossibleFlagsT::ReprT>(v) {
+            Some(repr) => from_repr(repr, self.lossy),
+            None => Err(de::Error::custom(format_args!(
+                "{v} is out of range for the repr of {}",
+                core::any::type_name::<PossibleFlagsT>()
+            ))),
+        }
+    }
+}
+
+impl<'de, PossibleFlagsT: BitBaggable> de::Visitor<'de> for Visitor<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: fmt::Binary,
+{
+    type Value = BitBag<PossibleFlagsT>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of flag names, or an integer")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bag = BitBag::empty();
+        while let Some(name) = seq.next_element::<String>()? {
+            match (by_name::<PossibleFlagsT>(&name), self.lossy) {
+                (Some(repr), _) => {
+                    bag.set_raw(repr);
+                }
+                (None, true) => {}
+                (None, false) => {
+                    return Err(de::Error::custom(
+                        ParseError::<PossibleFlagsT>::UnknownName(name),
+                    ))
+                }
+            }
+        }
+        Ok(bag)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_int(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_int(v)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        self.visit_int(v)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        self.visit_int(v)
+    }
+}
+
+/// Always represent a bag as a list of flag names, like `["A", "B"]`, regardless of format.
+///
+/// For use with `#[serde(with = "bitbag::serde::as_names")]`.
+/// Bags with unrecognised bits can't be serialized this way.
+pub mod as_names {
+    use super::*;
+    use ::serde::ser::SerializeSeq as _;
+
+    pub fn serialize<S, PossibleFlagsT>(
+        bag: &BitBag<PossibleFlagsT>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        if let Some(unrecognised) = bag.unrecognised_bits() {
+            return Err(ser::Error::custom(format_args!(
+                "cannot serialize as names: {}",
+                NonFlagBits::<PossibleFlagsT> { unrecognised }
+            )));
+        }
+        let names = || bag.iter_names();
+        // Some formats need to know the length up front
+        let mut seq = serializer.serialize_seq(Some(names().count()))?;
+        for name in names() {
+            seq.serialize_element(name)?;
+        }
+        seq.end()
+    }
+
+    /// Rejects names which aren't defined in the enum
+    pub fn deserialize<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        deserializer.deserialize_seq(Visitor::<PossibleFlagsT> {
+            lossy: false,
+            _flags: PhantomData,
+        })
+    }
+
+    /// Ignores names which aren't defined in the enum
+    pub fn deserialize_lossy<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        deserializer.deserialize_seq(Visitor::<PossibleFlagsT> {
+            lossy: true,
+            _flags: PhantomData,
+        })
+    }
+}
+
+/// Always represent a bag as its inner primitive, regardless of format.
+///
+/// For use with `#[serde(with = "bitbag::serde::as_bits")]`.
+pub mod as_bits {
+    use super::*;
+
+    pub fn serialize<S, PossibleFlagsT>(
+        bag: &BitBag<PossibleFlagsT>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: Serialize,
+    {
+        bag.repr.serialize(serializer)
+    }
+
+    /// Rejects bits which aren't defined in the enum
+    pub fn deserialize<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+    {
+        PossibleFlagsT::ReprT::deserialize(deserializer).and_then(|repr| from_repr(repr, false))
+    }
+
+    /// Discards bits which aren't defined in the enum
+    pub fn deserialize_lossy<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: Deserialize<'de> + fmt::Binary,
+    {
+        PossibleFlagsT::ReprT::deserialize(deserializer).and_then(|repr| from_repr(repr, true))
+    }
+}
+
+/// Represent a bag as a map of every declared flag name to whether it is set, like `{"A": true, "B": false}`.
+///
+/// For use with `#[serde(with = "bitbag::serde::as_bool_map")]`.
+/// Flags missing from the map are deserialized as unset, which suits partial updates from forms.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable};
+/// # #[derive(BitBaggable, Debug)]
+/// # #[repr(u8)]
+/// # enum Flags {
+/// #     A = 0b0001,
+/// #     B = 0b0010,
+/// # }
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Form {
+///     #[serde(with = "bitbag::serde::as_bool_map")]
+///     flags: BitBag<Flags>,
+/// }
+///
+/// let form = Form { flags: BitBag::new(0b0010) };
+/// assert_eq!(
+///     serde_json::to_string(&form).unwrap(),
+///     r#"{"flags":{"A":false,"B":true}}"#
+/// );
+/// let form = serde_json::from_str::<Form>(r#"{"flags":{"A":true}}"#).unwrap();
+/// assert_eq!(form.flags.get(), 0b0001);
+/// ```
+pub mod as_bool_map {
+    use super::*;
+    use ::serde::de::MapAccess;
+
+    pub fn serialize<S, PossibleFlagsT>(
+        bag: &BitBag<PossibleFlagsT>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        if let Some(unrecognised) = bag.unrecognised_bits() {
+            return Err(ser::Error::custom(format_args!(
+                "cannot serialize as a map: {}",
+                NonFlagBits::<PossibleFlagsT> { unrecognised }
+            )));
+        }
+        serializer.collect_map(
+            variants_in::<PossibleFlagsT>(PossibleFlagsT::ORDER)
+                .map(|(name, _, repr)| (name, bag.is_set_raw(*repr))),
+        )
+    }
+
+    /// Rejects names which aren't defined in the enum
+    pub fn deserialize<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        deserializer.deserialize_map(Visitor::<PossibleFlagsT> {
+            lossy: false,
+            _flags: PhantomData,
+        })
+    }
+
+    /// Ignores names which aren't defined in the enum.
+    ///
+    /// For use with `#[serde(serialize_with = "bitbag::serde::as_bool_map::serialize", deserialize_with = "bitbag::serde::as_bool_map::deserialize_lossy")]`.
+    pub fn deserialize_lossy<'de, D, PossibleFlagsT>(
+        deserializer: D,
+    ) -> Result<BitBag<PossibleFlagsT>, D::Error>
+    where
+        D: Deserializer<'de>,
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        deserializer.deserialize_map(Visitor::<PossibleFlagsT> {
+            lossy: true,
+            _flags: PhantomData,
+        })
+    }
+
+    struct Visitor<PossibleFlagsT> {
+        lossy: bool,
+        _flags: PhantomData<fn() -> PossibleFlagsT>,
+    }
+
+    impl<'de, PossibleFlagsT: BitBaggable> de::Visitor<'de> for Visitor<PossibleFlagsT>
+    where
+        PossibleFlagsT::ReprT: fmt::Binary,
+    {
+        type Value = BitBag<PossibleFlagsT>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of flag names to booleans")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut bag = BitBag::empty();
+            while let Some((name, set)) = map.next_entry::<String, bool>()? {
+                match (by_name::<PossibleFlagsT>(&name), self.lossy) {
+                    (Some(repr), _) => {
+                        match set {
+                            true => bag.set_raw(repr),
+                            false => bag.unset_raw(repr),
+                        };
+                    }
+                    (None, true) => {}
+                    (None, false) => {
+                        return Err(de::Error::custom(
+                            ParseError::<PossibleFlagsT>::UnknownName(name),
+                        ))
+                    }
+                }
+            }
+            Ok(bag)
+        }
+    }
+}
+
+/// A [`WideBitBag`] is serialized as a list of flag names for human-readable formats,
+/// and as a tuple of its `N * 8` bytes for binary formats, each word little-endian as in [`WideBitBag::encode_into`].
+///
+/// Either way, deserialization rejects bits and names which aren't defined in the enum.
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Serialize for WideBitBag<PossibleFlagsT, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ::serde::ser::{SerializeSeq as _, SerializeTuple as _};
+
+        if !serializer.is_human_readable() {
+            let mut tuple = serializer.serialize_tuple(Self::ENCODED_LEN)?;
+            for byte in self.words.iter().flat_map(|word| word.to_le_bytes()) {
+                tuple.serialize_element(&byte)?;
+            }
+            return tuple.end();
+        }
+        if self.has_unrecognised_bits() {
+            return Err(ser::Error::custom(
+                "cannot serialize as names: the bag has unrecognised bits",
+            ));
+        }
+        let names = || {
+            PossibleFlagsT::VARIANTS
+                .iter()
+                .filter(|(_, _, bit)| self.words[bit / 64] & (1 << (bit % 64)) != 0)
+                .map(|(name, _, _)| name)
+        };
+        let mut seq = serializer.serialize_seq(Some(names().count()))?;
+        for name in names() {
+            seq.serialize_element(name)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, PossibleFlagsT: WideBitBaggable, const N: usize> Deserialize<'de>
+    for WideBitBag<PossibleFlagsT, N>
+where
+    PossibleFlagsT: Clone,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_seq(WideNamesVisitor(PhantomData)),
+            false => {
+                deserializer.deserialize_tuple(Self::ENCODED_LEN, WideBytesVisitor(PhantomData))
+            }
+        }
+    }
+}
+
+struct WideNamesVisitor<PossibleFlagsT, const N: usize>(PhantomData<fn() -> PossibleFlagsT>);
+
+impl<'de, PossibleFlagsT: WideBitBaggable, const N: usize> de::Visitor<'de>
+    for WideNamesVisitor<PossibleFlagsT, N>
+where
+    PossibleFlagsT: Clone,
+{
+    type Value = WideBitBag<PossibleFlagsT, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of flag names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bag = WideBitBag::empty();
+        while let Some(name) = seq.next_element::<String>()? {
+            match PossibleFlagsT::VARIANTS
+                .iter()
+                .find(|(it, _, _)| *it == name)
+            {
+                Some((_, flag, _)) => bag.set(flag.clone()),
+                None => {
+                    return Err(de::Error::custom(format_args!(
+                        "{name:?} isn't the name of a flag in the enum {}",
+                        core::any::type_name::<PossibleFlagsT>()
+                    )))
+                }
+            };
+        }
+        Ok(bag)
+    }
+}
+
+struct WideBytesVisitor<PossibleFlagsT, const N: usize>(PhantomData<fn() -> PossibleFlagsT>);
+
+impl<'de, PossibleFlagsT: WideBitBaggable, const N: usize> de::Visitor<'de>
+    for WideBytesVisitor<PossibleFlagsT, N>
+{
+    type Value = WideBitBag<PossibleFlagsT, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a tuple of {} bytes", N * 8)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut words = [0; N];
+        for (word_ix, word) in words.iter_mut().enumerate() {
+            let mut bytes = [0; 8];
+            for (byte_ix, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(word_ix * 8 + byte_ix, &self))?;
+            }
+            *word = u64::from_le_bytes(bytes);
+        }
+        WideBitBag::new_strict(words).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    #[derive(Debug, Deserialize)]
+    #[serde(crate = "::serde")]
+    struct Config {
+        #[serde(deserialize_with = "deserialize_lossy")]
+        flags: BitBag<FooFlags>,
+    }
+
+    #[test]
+    fn human_readable() {
+        let bag = A | D;
+        let json = serde_json::to_string(&bag).unwrap();
+        assert_eq!(json, r#"["A","D"]"#);
+        assert_eq!(
+            serde_json::from_str::<BitBag<FooFlags>>(&json).unwrap(),
+            bag
+        );
+        assert_eq!(serde_json::from_str::<BitBag<FooFlags>>("9").unwrap(), bag);
+        assert_eq!(
+            serde_json::to_string(&BitBag::<FooFlags>::empty()).unwrap(),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn compact() {
+        let bag = A | D;
+        let bytes = postcard::to_allocvec(&bag).unwrap();
+        assert_eq!(bytes, [0b0000_1001]);
+        assert_eq!(
+            postcard::from_bytes::<BitBag<FooFlags>>(&bytes).unwrap(),
+            bag
+        );
+        assert!(postcard::from_bytes::<BitBag<FooFlags>>(&[0b1000_0000]).is_err());
+    }
+
+    #[test]
+    fn encoding() {
+        use crate as bitbag;
+
+        #[derive(BitBaggable)]
+        #[bitbag(serde = "bits")]
+        #[repr(u8)]
+        enum Bits {
+            A = 0b0001,
+        }
+
+        #[derive(BitBaggable)]
+        #[bitbag(serde = "names")]
+        #[repr(u8)]
+        enum Names {
+            A = 0b0001,
+        }
+
+        let bits = BitBag::<Bits>::all();
+        assert_eq!(serde_json::to_string(&bits).unwrap(), "1");
+        assert!(serde_json::from_str::<BitBag<Bits>>(r#"["A"]"#).is_err());
+
+        let names = BitBag::<Names>::all();
+        let bytes = postcard::to_allocvec(&names).unwrap();
+        assert_eq!(bytes, [1, 1, b'A']);
+        assert_eq!(
+            postcard::from_bytes::<BitBag<Names>>(&bytes).unwrap(),
+            names
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let e = serde_json::from_str::<BitBag<FooFlags>>("129").unwrap_err();
+        assert!(e.to_string().contains("0b10000000"));
+        let e = serde_json::from_str::<BitBag<FooFlags>>(r#"["A","E"]"#).unwrap_err();
+        assert!(e.to_string().contains(r#""E" is not a flag"#));
+        assert!(serde_json::from_str::<BitBag<FooFlags>>("256").is_err());
+        assert!(serde_json::to_string(&BitBag::<FooFlags>::new(0b1000_0000)).is_err());
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(crate = "::serde")]
+    struct Form {
+        #[serde(with = "as_bool_map")]
+        flags: BitBag<FooFlags>,
+    }
+
+    #[test]
+    fn bool_map() {
+        let form = Form { flags: B | C };
+        let json = serde_json::to_string(&form).unwrap();
+        assert_eq!(json, r#"{"flags":{"A":false,"B":true,"C":true,"D":false}}"#);
+        assert_eq!(serde_json::from_str::<Form>(&json).unwrap().flags, B | C);
+
+        let form = serde_json::from_str::<Form>(r#"{"flags":{"D":true}}"#).unwrap();
+        assert_eq!(form.flags, BitBag::just(D));
+        assert!(serde_json::from_str::<Form>(r#"{"flags":{"E":true}}"#).is_err());
+        assert!(serde_json::from_str::<Form>(r#"{"flags":{"A":1}}"#).is_err());
+
+        let form = Form { flags: B | C };
+        let bytes = postcard::to_allocvec(&form).unwrap();
+        assert_eq!(postcard::from_bytes::<Form>(&bytes).unwrap().flags, B | C);
+    }
+
+    #[test]
+    fn lossy_bool_map() {
+        #[derive(Debug, Deserialize)]
+        #[serde(crate = "::serde")]
+        struct LossyForm {
+            #[serde(deserialize_with = "as_bool_map::deserialize_lossy")]
+            flags: BitBag<FooFlags>,
+        }
+        let form = serde_json::from_str::<LossyForm>(r#"{"flags":{"A":true,"E":true}}"#).unwrap();
+        assert_eq!(form.flags, BitBag::just(A));
+    }
+
+    #[test]
+    fn lossy_wrapper() {
+        let Lossy(bag) = serde_json::from_str::<Lossy<FooFlags>>(r#"["B","E"]"#).unwrap();
+        assert_eq!(bag, BitBag::just(B));
+        let Lossy(bag) = postcard::from_bytes::<Lossy<FooFlags>>(&[0b1000_0100]).unwrap();
+        assert_eq!(bag, BitBag::just(C));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "::serde")]
+    struct Fields {
+        #[serde(with = "as_names")]
+        names: BitBag<FooFlags>,
+        #[serde(with = "as_bits")]
+        bits: BitBag<FooFlags>,
+    }
+
+    #[test]
+    fn field_helpers() {
+        let fields = Fields {
+            names: A | B,
+            bits: A | B,
+        };
+        let json = serde_json::to_string(&fields).unwrap();
+        assert_eq!(json, r#"{"names":["A","B"],"bits":3}"#);
+        assert_eq!(serde_json::from_str::<Fields>(&json).unwrap(), fields);
+        assert!(serde_json::from_str::<Fields>(r#"{"names":3,"bits":3}"#).is_err());
+        assert!(serde_json::from_str::<Fields>(r#"{"names":[],"bits":["A"]}"#).is_err());
+
+        let bytes = postcard::to_allocvec(&fields).unwrap();
+        assert_eq!(bytes, [2, 1, b'A', 1, b'B', 3]);
+        assert_eq!(postcard::from_bytes::<Fields>(&bytes).unwrap(), fields);
+    }
+
+    #[test]
+    fn lossy() {
+        let config = serde_json::from_str::<Config>(r#"{"flags": 129}"#).unwrap();
+        assert_eq!(config.flags, BitBag::just(A));
+        let config = serde_json::from_str::<Config>(r#"{"flags": ["A", "E"]}"#).unwrap();
+        assert_eq!(config.flags, BitBag::just(A));
+    }
+
+    #[test]
+    fn wide() {
+        use crate as bitbag;
+
+        #[derive(Debug, Clone, WideBitBaggable)]
+        #[repr(u8)]
+        enum Wide {
+            Low = 0,
+            High = 70,
+        }
+
+        let bag: WideBitBag<Wide, 2> = vec![Wide::Low, Wide::High].into_iter().collect();
+        let json = serde_json::to_string(&bag).unwrap();
+        assert_eq!(json, r#"["Low","High"]"#);
+        assert_eq!(
+            serde_json::from_str::<WideBitBag<Wide, 2>>(&json).unwrap(),
+            bag
+        );
+        assert!(serde_json::from_str::<WideBitBag<Wide, 2>>(r#"["Mid"]"#).is_err());
+        assert!(serde_json::to_string(&WideBitBag::<Wide, 2>::new([2, 0])).is_err());
+
+        let bytes = postcard::to_allocvec(&bag).unwrap();
+        // Fixed-width words, even though postcard encodes a `u64` as a varint
+        assert_eq!(bytes, [1, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0]);
+        let mut encoded = [0; 16];
+        bag.encode_into(&mut encoded).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(
+            postcard::from_bytes::<WideBitBag<Wide, 2>>(&bytes).unwrap(),
+            bag
+        );
+        let mut unrecognised = [0; 16];
+        unrecognised[0] = 2;
+        assert!(postcard::from_bytes::<WideBitBag<Wide, 2>>(&unrecognised).is_err());
+        assert!(postcard::from_bytes::<WideBitBag<Wide, 2>>(&bytes[..15]).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! A bag guarded by [critical-section](https://docs.rs/critical-section), see [`SharedBitBag`].
+//! Enabled by the `critical-section` feature.
+
+use crate::{BitBag, BitBagCell, BitBaggable};
+use ::critical_section::{CriticalSection, Mutex};
+use core::fmt::{self, Debug};
+
+/// A [`BitBag`] which can be shared between interrupt handlers and the main thread,
+/// on targets without atomics.
+///
+/// Each method runs in its own critical section.
+/// To make several changes in one, use [`SharedBitBag::borrow`] inside [`critical_section::with`](::critical_section::with).
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, SharedBitBag};
+/// #[derive(Debug, Clone, Copy, BitBaggable)]
+/// #[repr(u8)]
+/// enum Event {
+///     Rx = 0b01,
+///     Tx = 0b10,
+/// }
+///
+/// static EVENTS: SharedBitBag<Event> = SharedBitBag::new(BitBag::new(0));
+///
+/// // in the interrupt handler
+/// EVENTS.insert(Event::Rx);
+///
+/// // in the main loop
+/// for event in EVENTS.take() {
+///     // ...
+/// #   assert!(matches!(event, Event::Rx));
+/// }
+/// assert!(EVENTS.load().is_empty());
+/// ```
+pub struct SharedBitBag<PossibleFlagsT: BitBaggable> {
+    inner: Mutex<BitBagCell<PossibleFlagsT>>,
+}
+
+impl<PossibleFlagsT: BitBaggable> SharedBitBag<PossibleFlagsT> {
+    pub const fn new(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self {
+            inner: Mutex::new(BitBagCell::new(bag)),
+        }
+    }
+
+    pub fn into_inner(self) -> BitBag<PossibleFlagsT> {
+        self.inner.into_inner().into_inner()
+    }
+
+    /// The bag as a [`BitBagCell`], for the duration of the critical section
+    pub fn borrow<'cs>(&'cs self, cs: CriticalSection<'cs>) -> &'cs BitBagCell<PossibleFlagsT> {
+        self.inner.borrow(cs)
+    }
+
+    pub fn load(&self) -> BitBag<PossibleFlagsT> {
+        ::critical_section::with(|cs| self.borrow(cs).get())
+    }
+
+    pub fn store(&self, bag: BitBag<PossibleFlagsT>) {
+        ::critical_section::with(|cs| self.borrow(cs).set(bag))
+    }
+
+    /// Set `flag`, returning whether any of its bits weren't already set, as [`BitBag::insert`]
+    pub fn insert(&self, flag: PossibleFlagsT) -> bool {
+        ::critical_section::with(|cs| {
+            let cell = self.borrow(cs);
+            let mut bag = cell.get();
+            let changed = bag.insert(flag);
+            cell.set(bag);
+            changed
+        })
+    }
+
+    /// Unset `flag`, returning whether any of its bits were set, as [`BitBag::remove`]
+    pub fn remove(&self, flag: PossibleFlagsT) -> bool {
+        ::critical_section::with(|cs| {
+            let cell = self.borrow(cs);
+            let mut bag = cell.get();
+            let changed = bag.remove(flag);
+            cell.set(bag);
+            changed
+        })
+    }
+
+    /// Clear the bag, returning the flags which were set
+    pub fn take(&self) -> BitBag<PossibleFlagsT> {
+        ::critical_section::with(|cs| self.borrow(cs).replace(BitBag::empty()))
+    }
+
+    /// Replace the bag with the result of `f`, returning the new bag
+    pub fn update(
+        &self,
+        f: impl FnOnce(BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT>,
+    ) -> BitBag<PossibleFlagsT> {
+        ::critical_section::with(|cs| self.borrow(cs).update(f))
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Default for SharedBitBag<PossibleFlagsT> {
+    fn default() -> Self {
+        Self::new(BitBag::empty())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>> for SharedBitBag<PossibleFlagsT> {
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        Self::new(bag)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Debug for SharedBitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedBitBag").field(&self.load()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedBitBag;
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+
+    #[test]
+    fn flags() {
+        let bag = SharedBitBag::new(BitBag::just(A));
+        assert!(bag.insert(B));
+        assert!(!bag.insert(B));
+        assert!(bag.remove(A));
+        assert!(!bag.remove(A));
+        assert_eq!(bag.load(), BitBag::just(B));
+    }
+
+    #[test]
+    fn threads() {
+        let bag = SharedBitBag::<FooFlags>::default();
+        std::thread::scope(|scope| {
+            for flag in [A, B, C] {
+                let bag = &bag;
+                scope.spawn(move || bag.insert(flag));
+            }
+        });
+        assert!(bag.remove(B));
+        assert_eq!(bag.update(|bag| bag | D), A | C | D);
+        ::critical_section::with(|cs| {
+            let cell = bag.borrow(cs);
+            cell.unset_flag(A);
+            cell.unset_flag(C);
+        });
+        assert_eq!(bag.take(), BitBag::just(D));
+        assert!(bag.into_inner().is_empty());
+    }
+}
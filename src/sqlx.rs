@@ -0,0 +1,121 @@
+//! [sqlx](https://docs.rs/sqlx) support, enabled by the `sqlx` feature.
+//!
+//! A [`BitBag`] is stored in the integer column type sqlx uses for its inner primitive on each database,
+//! so it works wherever the primitive does (for example, Postgres has no unsigned integers, so needs a signed repr).
+//! Decoding permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+//! Use [`Strict`] to reject them instead.
+
+use crate::{BitBag, BitBaggable};
+use ::sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type};
+use std::fmt::{Binary, Debug};
+
+impl<DB: Database, PossibleFlagsT: BitBaggable> Type<DB> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        PossibleFlagsT::ReprT::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        PossibleFlagsT::ReprT::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database, PossibleFlagsT: BitBaggable> Encode<'q, DB> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        self.repr.encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.repr.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.repr.size_hint()
+    }
+}
+
+impl<'r, DB: Database, PossibleFlagsT: BitBaggable> Decode<'r, DB> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        PossibleFlagsT::ReprT::decode(value).map(BitBag::new)
+    }
+}
+
+/// Stored like a [`BitBag`], but decoding fails if there are bits set which aren't defined in the enum.
+pub struct Strict<PossibleFlagsT: BitBaggable>(pub BitBag<PossibleFlagsT>);
+
+impl<DB: Database, PossibleFlagsT: BitBaggable> Type<DB> for Strict<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        PossibleFlagsT::ReprT::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        PossibleFlagsT::ReprT::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database, PossibleFlagsT: BitBaggable> Encode<'q, DB> for Strict<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        self.0.encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.0.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<'r, DB: Database, PossibleFlagsT: BitBaggable> Decode<'r, DB> for Strict<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Decode<'r, DB> + Binary + Debug + Send + Sync,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let repr = PossibleFlagsT::ReprT::decode(value)?;
+        Ok(Strict(BitBag::new_strict(repr)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Strict;
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+    use ::sqlx::{Connection as _, SqliteConnection};
+
+    #[tokio::test]
+    async fn sqlite() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        let bag: BitBag<FooFlags> = ::sqlx::query_scalar("SELECT ?")
+            .bind(A | C)
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(bag, A | C);
+
+        let bag: BitBag<FooFlags> = ::sqlx::query_scalar("SELECT 255")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(bag.get(), 255);
+        assert!(::sqlx::query_scalar::<_, Strict<FooFlags>>("SELECT 255")
+            .fetch_one(&mut conn)
+            .await
+            .is_err());
+    }
+}
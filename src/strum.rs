@@ -0,0 +1,106 @@
+//! Reuse [strum](https://docs.rs/strum)'s name tables instead of the variant identifiers.
+//!
+//! If your enum derives `EnumString` and `IntoStaticStr`, these functions honour any
+//! `#[strum(serialize = "...")]` renames, so there's only one set of names to maintain.
+//! ```
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use strum::{EnumString, IntoStaticStr};
+//!
+//! #[derive(Debug, BitBaggable, BitOr, EnumString, IntoStaticStr)]
+//! #[strum(serialize_all = "snake_case")]
+//! #[repr(u8)]
+//! enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//!     #[strum(serialize = "x")]
+//!     Execute = 0b0100,
+//! }
+//!
+//! let bag = bitbag::strum::from_str::<Perm>("read | x").unwrap();
+//! assert_eq!(bitbag::strum::display(&bag).to_string(), "read | x");
+//! ```
+
+use crate::{parse::parse_with, BitBag, BitBaggable, ParseError, UnknownNames};
+use core::{fmt, str::FromStr};
+
+/// Like [`BitBag::from_str`](std::str::FromStr::from_str), but resolving names with the enum's [`FromStr`] implementation.
+///
+/// Numeric tokens are still accepted.
+pub fn from_str<PossibleFlagsT>(
+    s: &str,
+) -> Result<BitBag<PossibleFlagsT>, ParseError<PossibleFlagsT>>
+where
+    PossibleFlagsT: BitBaggable + FromStr,
+{
+    parse_with(
+        s,
+        |name| {
+            name.parse::<PossibleFlagsT>()
+                .ok()
+                .map(BitBaggable::into_repr)
+        },
+        UnknownNames::Error,
+    )
+    .map(|parsed| parsed.bag)
+}
+
+/// Format `bag` like its [`Display`](fmt::Display) implementation, but taking names from the enum's `IntoStaticStr` implementation
+pub fn display<PossibleFlagsT: BitBaggable>(
+    bag: &BitBag<PossibleFlagsT>,
+) -> Display<'_, PossibleFlagsT> {
+    Display { bag }
+}
+
+/// Returned by [`display`]
+pub struct Display<'a, PossibleFlagsT: BitBaggable> {
+    bag: &'a BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> fmt::Display for Display<'_, PossibleFlagsT>
+where
+    for<'a> &'static str: From<&'a PossibleFlagsT>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bag
+            .write_names_with(f, PossibleFlagsT::ORDER, |(_, flag, _)| {
+                <&'static str>::from(flag)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bitbag;
+    use crate::BitOr;
+    use strum::{EnumString, IntoStaticStr};
+
+    #[derive(Debug, BitBaggable, BitOr, EnumString, IntoStaticStr)]
+    #[strum(serialize_all = "kebab-case")]
+    #[repr(u16)]
+    enum Options {
+        DryRun = 0b0001,
+        Verbose = 0b0010,
+        #[strum(serialize = "no-color", serialize = "no-colour")]
+        NoColour = 0b0100,
+    }
+
+    #[test]
+    fn parse() {
+        let bag = from_str::<Options>("dry-run | no-colour").unwrap();
+        assert_eq!(bag, Options::DryRun | Options::NoColour);
+        let bag = from_str::<Options>("no-color | 0x2").unwrap();
+        assert_eq!(bag, Options::Verbose | Options::NoColour);
+        assert!(matches!(
+            from_str::<Options>("DryRun"),
+            Err(ParseError::UnknownName(_))
+        ));
+    }
+
+    #[test]
+    fn format() {
+        let bag = Options::DryRun | Options::Verbose;
+        assert_eq!(display(&bag).to_string(), "dry-run | verbose");
+        assert_eq!(display(&BitBag::<Options>::empty()).to_string(), "<unset>");
+    }
+}
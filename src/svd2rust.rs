@@ -0,0 +1,220 @@
+//! Adapters for the register and field types generated by [svd2rust](https://docs.rs/svd2rust), enabled by the `svd2rust` feature.
+//!
+//! svd2rust generates its reader and writer types into each PAC, rather than a shared crate,
+//! so [`svd2rust_read!`](crate::svd2rust_read) and [`svd2rust_write!`](crate::svd2rust_write)
+//! implement [`ReadBag`] and [`WriteBag`] for them in your crate instead.
+//! The bits are converted with [`From`], so an enum can be wider than the register or field it's read from,
+//! and narrower than the one it's written to.
+//! ```ignore
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u32)]
+//! enum Interrupt {
+//!     Rx = 0b01,
+//!     Tx = 0b10,
+//! }
+//!
+//! bitbag::svd2rust_read!(pac::uart0::isr::R => Interrupt);
+//! // `unsafe`, as svd2rust's `W::bits` is, because the SVD doesn't enumerate every valid value
+//! bitbag::svd2rust_write!(unsafe pac::uart0::ier::W => Interrupt);
+//!
+//! use bitbag::svd2rust::{ReadBag as _, WriteBag as _};
+//! let pending = uart.isr().read().bag();
+//! uart.ier().write(|w| w.bag(Interrupt::Rx | Interrupt::Tx));
+//! ```
+
+use crate::{BitBag, BitBaggable};
+
+/// A register or field reader which can be read as a bag, implemented with [`svd2rust_read!`](crate::svd2rust_read).
+pub trait ReadBag<PossibleFlagsT: BitBaggable> {
+    /// The read bits, permitting (and preserving) unrecognised bits, as [`BitBag::new`] does
+    fn bag(&self) -> BitBag<PossibleFlagsT>;
+}
+
+/// A register or field writer which can write a bag, implemented with [`svd2rust_write!`](crate::svd2rust_write).
+pub trait WriteBag<PossibleFlagsT: BitBaggable> {
+    /// The register writer, for chaining further writes
+    type Output;
+    fn bag(self, bag: BitBag<PossibleFlagsT>) -> Self::Output;
+}
+
+/// Implement [`ReadBag`](crate::svd2rust::ReadBag) for svd2rust register readers (`R`) or field readers (`FIELD_R`).
+/// ```ignore
+/// bitbag::svd2rust_read! {
+///     pac::uart0::isr::R => Interrupt,
+///     pac::uart0::cr::MODE_R => Mode,
+/// }
+/// ```
+#[macro_export]
+macro_rules! svd2rust_read {
+    ($($reader:ty => $flags:ty),* $(,)?) => {$(
+        impl $crate::svd2rust::ReadBag<$flags> for $reader {
+            fn bag(&self) -> $crate::BitBag<$flags> {
+                $crate::BitBag::new(::core::convert::From::from(self.bits()))
+            }
+        }
+    )*};
+}
+
+/// Implement [`WriteBag`](crate::svd2rust::WriteBag) for svd2rust register writers (`W`),
+/// or field writers (`FIELD_W`) along with the register writer they return.
+///
+/// Only the bits of declared flags are written, so unrecognised bits in the bag never reach the writer.
+///
+/// svd2rust marks the writer's `bits` method `unsafe` when the SVD doesn't say that every value is valid.
+/// Those writers need a leading `unsafe`, which asserts that any combination of the enum's flags is a valid value.
+/// ```ignore
+/// bitbag::svd2rust_write! {
+///     pac::uart0::ier::W => Interrupt,
+///     unsafe pac::uart0::ctrl::W => Control,
+///     field<'a> pac::uart0::cr::MODE_W<'a> => Mode, &'a mut pac::uart0::cr::W,
+///     unsafe field<'a> pac::uart0::cr::BAUD_W<'a> => Baud, &'a mut pac::uart0::cr::W,
+/// }
+/// ```
+/// Leaving out `unsafe` for such a writer doesn't compile.
+/// ```compile_fail,E0133
+/// # use bitbag::BitBaggable;
+/// # #[derive(BitBaggable)]
+/// # #[repr(u32)]
+/// # enum Interrupt {
+/// #     Rx = 0b01,
+/// # }
+/// struct W(u32);
+///
+/// impl W {
+///     unsafe fn bits(&mut self, bits: u32) -> &mut Self {
+///         self.0 = bits;
+///         self
+///     }
+/// }
+///
+/// bitbag::svd2rust_write!(W => Interrupt);
+/// ```
+#[macro_export]
+macro_rules! svd2rust_write {
+    () => {};
+    (unsafe field<$lt:lifetime> $writer:ty => $flags:ty, $output:ty $(, $($rest:tt)*)?) => {
+        impl<$lt> $crate::svd2rust::WriteBag<$flags> for $writer {
+            type Output = $output;
+
+            fn bag(self, bag: $crate::BitBag<$flags>) -> $output {
+                let bits = $crate::BitBag::<$flags>::new_masked(bag.get()).get();
+                // The caller asserted that any combination of declared flags is valid
+                unsafe { self.bits(::core::convert::From::from(bits)) }
+            }
+        }
+        $crate::svd2rust_write!($($($rest)*)?);
+    };
+    (unsafe $writer:ty => $flags:ty $(, $($rest:tt)*)?) => {
+        impl<'w> $crate::svd2rust::WriteBag<$flags> for &'w mut $writer {
+            type Output = &'w mut $writer;
+
+            fn bag(self, bag: $crate::BitBag<$flags>) -> &'w mut $writer {
+                let bits = $crate::BitBag::<$flags>::new_masked(bag.get()).get();
+                // The caller asserted that any combination of declared flags is valid
+                unsafe { self.bits(::core::convert::From::from(bits)) }
+            }
+        }
+        $crate::svd2rust_write!($($($rest)*)?);
+    };
+    (field<$lt:lifetime> $writer:ty => $flags:ty, $output:ty $(, $($rest:tt)*)?) => {
+        impl<$lt> $crate::svd2rust::WriteBag<$flags> for $writer {
+            type Output = $output;
+
+            fn bag(self, bag: $crate::BitBag<$flags>) -> $output {
+                let bits = $crate::BitBag::<$flags>::new_masked(bag.get()).get();
+                self.bits(::core::convert::From::from(bits))
+            }
+        }
+        $crate::svd2rust_write!($($($rest)*)?);
+    };
+    ($writer:ty => $flags:ty $(, $($rest:tt)*)?) => {
+        impl<'w> $crate::svd2rust::WriteBag<$flags> for &'w mut $writer {
+            type Output = &'w mut $writer;
+
+            fn bag(self, bag: $crate::BitBag<$flags>) -> &'w mut $writer {
+                let bits = $crate::BitBag::<$flags>::new_masked(bag.get()).get();
+                self.bits(::core::convert::From::from(bits))
+            }
+        }
+        $crate::svd2rust_write!($($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReadBag, WriteBag as _};
+    use crate as bitbag;
+    use crate::tests::FooFlags::{self, *};
+    use crate::{BitBag, BitBaggable};
+
+    // shaped like svd2rust's generated types
+    struct R(u32);
+    struct W(u32);
+    struct FieldReader(u8);
+    struct FieldWriter<'a>(&'a mut W);
+
+    impl R {
+        fn bits(&self) -> u8 {
+            self.0 as u8
+        }
+    }
+
+    impl W {
+        unsafe fn bits(&mut self, bits: u32) -> &mut Self {
+            self.0 = bits;
+            self
+        }
+    }
+
+    impl FieldReader {
+        fn bits(&self) -> u8 {
+            self.0
+        }
+    }
+
+    impl<'a> FieldWriter<'a> {
+        fn bits(self, bits: u8) -> &'a mut W {
+            self.0 .0 = self.0 .0 & !0xf0 | u32::from(bits) << 4;
+            self.0
+        }
+    }
+
+    crate::svd2rust_read!(R => FooFlags, FieldReader => FooFlags);
+    crate::svd2rust_write! {
+        unsafe W => Wide,
+        field<'a> FieldWriter<'a> => FooFlags, &'a mut W,
+    }
+
+    #[derive(Debug, BitBaggable)]
+    #[repr(u32)]
+    enum Wide {
+        Low = 1,
+        High = 1 << 31,
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(ReadBag::bag(&R(0b0101)), A | C);
+        let bag: BitBag<FooFlags> = FieldReader(0b1000_0001).bag();
+        assert_eq!(bag.get(), 0b1000_0001);
+    }
+
+    #[test]
+    fn write() {
+        let mut w = W(0);
+        let bag = BitBag::<Wide>::of([Wide::High, Wide::Low]);
+        w.bag(bag);
+        assert_eq!(w.0, 1 << 31 | 1);
+        FieldWriter(&mut w).bag(B | C);
+        assert_eq!(w.0, 1 << 31 | 0b0110_0001);
+    }
+
+    #[test]
+    fn unrecognised_bits() {
+        let mut w = W(0);
+        w.bag(BitBag::<Wide>::new(u32::MAX));
+        assert_eq!(w.0, 1 << 31 | 1);
+        FieldWriter(&mut w).bag(BitBag::new(0b1111_0010));
+        assert_eq!(w.0, 1 << 31 | 0b0010_0001);
+    }
+}
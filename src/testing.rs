@@ -0,0 +1,314 @@
+//! Assertions for downstream test suites, which check that a [`BitBaggable`] enum behaves consistently.
+//!
+//! Each helper checks every variant, every pair of variants, and the empty and full bags,
+//! panicking with a description of the first mismatch.
+//! ```
+//! # use bitbag::BitBaggable;
+//! #[derive(BitBaggable, Debug, Clone)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! // in your test suite
+//! bitbag::testing::assert_display_roundtrip::<Flags>();
+//! bitbag::testing::assert_repr_roundtrip::<Flags>();
+//! ```
+
+use crate::{BitBag, BitBaggable};
+use core::{
+    fmt::{Binary, Debug},
+    iter,
+};
+
+/// Check that [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr) agree for every sample bag.
+pub fn assert_display_roundtrip<PossibleFlagsT>()
+where
+    PossibleFlagsT: BitBaggable + Debug,
+    PossibleFlagsT::ReprT: Binary + Debug,
+{
+    for bag in samples::<PossibleFlagsT>() {
+        laws::assert_display_roundtrip(bag);
+    }
+}
+
+/// Check that every variant's [`BitBaggable::into_repr`] and [`BitBaggable::name`] agree with [`BitBaggable::VARIANTS`],
+/// and that every sample bag survives a trip through its primitive.
+pub fn assert_repr_roundtrip<PossibleFlagsT>()
+where
+    PossibleFlagsT: BitBaggable + Debug + Clone,
+    PossibleFlagsT::ReprT: Binary + Debug,
+{
+    for (name, flag, repr) in PossibleFlagsT::VARIANTS {
+        assert_eq!(
+            flag.clone().into_repr(),
+            *repr,
+            "{flag:?}.into_repr() doesn't match its entry in VARIANTS"
+        );
+        assert_eq!(
+            flag.clone().name(),
+            *name,
+            "{flag:?}.name() doesn't match its entry in VARIANTS"
+        );
+        let bag = BitBag::<PossibleFlagsT>::new(*repr);
+        assert!(
+            bag.is_set(flag.clone()),
+            "{}",
+            format_args!("{flag:?} isn't set in {bag:?}")
+        );
+    }
+    for bag in samples::<PossibleFlagsT>() {
+        match BitBag::<PossibleFlagsT>::new_strict(bag.get()) {
+            Ok(strict) => assert_eq!(strict, bag),
+            Err(e) => panic!("{}", format_args!("{bag:?} was built from flags, but {e}")),
+        }
+    }
+}
+
+/// Every variant, every pair of variants, and the empty and full bags
+fn samples<PossibleFlagsT: BitBaggable>() -> impl Iterator<Item = BitBag<PossibleFlagsT>> {
+    let variants = PossibleFlagsT::VARIANTS;
+    let singles = variants.iter().map(|(_, _, repr)| BitBag::new(*repr));
+    let pairs = variants
+        .iter()
+        .enumerate()
+        .flat_map(move |(ix, (_, _, left))| {
+            variants[ix + 1..]
+                .iter()
+                .map(move |(_, _, right)| *BitBag::new(*left).set_raw(*right))
+        });
+    iter::once(BitBag::empty())
+        .chain(iter::once(BitBag::all()))
+        .chain(singles)
+        .chain(pairs)
+}
+
+/// Checks of the algebraic laws bags should obey, for downstream fuzz and property-test harnesses.
+///
+/// Unlike the helpers above, these take the bags to check, so the harness chooses the inputs.
+/// Each panics with a description of the inputs if the law doesn't hold.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, BitOr};
+/// use bitbag::testing::laws;
+/// # #[derive(BitBaggable, BitOr, Debug)]
+/// # #[repr(u8)]
+/// # enum Flags {
+/// #     A = 0b0001,
+/// #     B = 0b0010,
+/// # }
+///
+/// // with bags from proptest, quickcheck or a fuzzer
+/// let (a, b) = (Flags::A | Flags::B, BitBag::just(Flags::B));
+/// laws::assert_union_commutative(a, b);
+/// laws::assert_de_morgan(a, b);
+/// laws::assert_display_roundtrip(a);
+/// ```
+pub mod laws {
+    use crate::{BitBag, BitBaggable};
+    use alloc::string::ToString as _;
+    use core::fmt::{Binary, Debug};
+
+    /// `(a | b) | c == a | (b | c)`
+    pub fn assert_union_associative<PossibleFlagsT: BitBaggable>(
+        a: BitBag<PossibleFlagsT>,
+        b: BitBag<PossibleFlagsT>,
+        c: BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT::ReprT: Debug,
+    {
+        assert_eq!(
+            (a | b) | c,
+            a | (b | c),
+            "union of {a:?}, {b:?} and {c:?} isn't associative"
+        );
+    }
+
+    /// `a | b == b | a`
+    pub fn assert_union_commutative<PossibleFlagsT: BitBaggable>(
+        a: BitBag<PossibleFlagsT>,
+        b: BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT::ReprT: Debug,
+    {
+        assert_eq!(a | b, b | a, "union of {a:?} and {b:?} isn't commutative");
+    }
+
+    /// `(a & b) & c == a & (b & c)`
+    pub fn assert_intersection_associative<PossibleFlagsT: BitBaggable>(
+        a: BitBag<PossibleFlagsT>,
+        b: BitBag<PossibleFlagsT>,
+        c: BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT::ReprT: Debug,
+    {
+        assert_eq!(
+            (a & b) & c,
+            a & (b & c),
+            "intersection of {a:?}, {b:?} and {c:?} isn't associative"
+        );
+    }
+
+    /// `a & b == b & a`
+    pub fn assert_intersection_commutative<PossibleFlagsT: BitBaggable>(
+        a: BitBag<PossibleFlagsT>,
+        b: BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT::ReprT: Debug,
+    {
+        assert_eq!(
+            a & b,
+            b & a,
+            "intersection of {a:?} and {b:?} isn't commutative"
+        );
+    }
+
+    /// `!(a | b) == !a & !b` and `!(a & b) == !a | !b`, compared within [`BitBag::all`].
+    pub fn assert_de_morgan<PossibleFlagsT: BitBaggable>(
+        a: BitBag<PossibleFlagsT>,
+        b: BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT::ReprT: Debug,
+    {
+        let all = BitBag::all();
+        assert_eq!(
+            !(a | b) & all,
+            (!a & !b) & all,
+            "De Morgan's law doesn't hold for the union of {a:?} and {b:?}"
+        );
+        assert_eq!(
+            !(a & b) & all,
+            (!a | !b) & all,
+            "De Morgan's law doesn't hold for the intersection of {a:?} and {b:?}"
+        );
+    }
+
+    /// [`Display`](std::fmt::Display) then [`FromStr`](std::str::FromStr) gives back `bag`.
+    ///
+    /// This holds for unions of declared flags, as the `arbitrary`, `proptest` and `quickcheck` features generate.
+    pub fn assert_display_roundtrip<PossibleFlagsT: BitBaggable>(bag: BitBag<PossibleFlagsT>)
+    where
+        PossibleFlagsT::ReprT: Binary + Debug,
+    {
+        let displayed = bag.to_string();
+        match displayed.parse::<BitBag<PossibleFlagsT>>() {
+            Ok(parsed) => assert_eq!(
+                parsed, bag,
+                "{displayed:?} parsed to {parsed:?}, but was displayed from {bag:?}"
+            ),
+            Err(e) => panic!(
+                "{}",
+                format_args!("{displayed:?} was displayed from {bag:?}, but failed to parse: {e}")
+            ),
+        }
+    }
+
+    /// Serializing with `serialize`, then deserializing with `deserialize`, gives back `bag`.
+    ///
+    /// Pass a format's functions, like `serde_json::to_string` and `|s| serde_json::from_str(s)`.
+    #[cfg(feature = "serde")]
+    pub fn assert_serde_roundtrip<PossibleFlagsT, S, SerErr, DeErr>(
+        bag: BitBag<PossibleFlagsT>,
+        serialize: impl FnOnce(&BitBag<PossibleFlagsT>) -> Result<S, SerErr>,
+        deserialize: impl FnOnce(&S) -> Result<BitBag<PossibleFlagsT>, DeErr>,
+    ) where
+        PossibleFlagsT: BitBaggable,
+        PossibleFlagsT::ReprT: Debug,
+        SerErr: core::fmt::Display,
+        DeErr: core::fmt::Display,
+    {
+        let serialized = match serialize(&bag) {
+            Ok(serialized) => serialized,
+            Err(e) => panic!("{}", format_args!("{bag:?} failed to serialize: {e}")),
+        };
+        match deserialize(&serialized) {
+            Ok(deserialized) => assert_eq!(
+                deserialized, bag,
+                "{bag:?} deserialized as {deserialized:?}"
+            ),
+            Err(e) => panic!(
+                "{}",
+                format_args!("{bag:?} was serialized, but failed to deserialize: {e}")
+            ),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::tests::FooFlags::{self, *};
+
+        #[test]
+        fn laws() {
+            let bags = [
+                BitBag::<FooFlags>::empty(),
+                BitBag::just(A),
+                A | C,
+                B | C | D,
+                BitBag::new(0xf0),
+            ];
+            for a in bags {
+                for b in bags {
+                    assert_union_commutative(a, b);
+                    assert_intersection_commutative(a, b);
+                    assert_de_morgan(a, b);
+                    for c in bags {
+                        assert_union_associative(a, b, c);
+                        assert_intersection_associative(a, b, c);
+                    }
+                }
+            }
+            for bag in &bags[..4] {
+                assert_display_roundtrip(*bag);
+            }
+        }
+
+        #[test]
+        #[should_panic = "failed to parse"]
+        fn unrecognised_bits() {
+            assert_display_roundtrip(BitBag::<FooFlags>::new(0xf1));
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            assert_serde_roundtrip(B | D, serde_json::to_string, |s| serde_json::from_str(s));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags;
+
+    #[derive(Debug, Clone)]
+    enum Mislabelled {
+        A,
+    }
+
+    impl BitBaggable for Mislabelled {
+        type ReprT = u8;
+        fn into_repr(self) -> Self::ReprT {
+            0b0001
+        }
+        const VARIANTS: &'static [(&'static str, Self, Self::ReprT)] = &[("A", Self::A, 0b0010)];
+    }
+
+    #[test]
+    fn derived() {
+        assert_display_roundtrip::<FooFlags>();
+        assert_repr_roundtrip::<FooFlags>();
+    }
+
+    #[test]
+    #[should_panic = "into_repr() doesn't match"]
+    fn mislabelled() {
+        assert_repr_roundtrip::<Mislabelled>();
+    }
+
+    #[test]
+    fn samples() {
+        assert_eq!(super::samples::<FooFlags>().count(), 2 + 4 + 6);
+    }
+}
@@ -0,0 +1,136 @@
+//! [tock-registers](https://docs.rs/tock-registers) support, enabled by the `tock-registers` feature.
+//!
+//! These extension traits are implemented for every register whose width matches the enum's repr,
+//! such as `ReadWrite<u32>` for a `#[repr(u32)]` enum.
+//! Read bags permit (and preserve) unrecognised bits, as [`BitBag::new`] does.
+//! ```
+//! # use bitbag::{BitBag, BitBaggable, BitOr};
+//! use bitbag::tock_registers::{ModifyBag as _, ReadBag as _, WriteBag as _};
+//! use tock_registers::registers::InMemoryRegister;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u32)]
+//! enum Control {
+//!     Enable = 0b01,
+//!     Interrupt = 0b10,
+//! }
+//!
+//! let register = InMemoryRegister::<u32>::new(0);
+//! register.write_bag(Control::Enable | Control::Interrupt);
+//! register.modify_bag(|bag| bag.unset(Control::Interrupt));
+//! assert_eq!(register.read_bag::<Control>().get(), 0b01);
+//! ```
+
+use crate::{BitBag, BitBaggable, FlagRegister};
+use ::tock_registers::{
+    interfaces::{Readable, Writeable},
+    registers::{InMemoryRegister, ReadWrite},
+    RegisterLongName, UIntLike,
+};
+use num::PrimInt;
+
+/// Read a register as a [`BitBag`]
+pub trait ReadBag: Readable {
+    fn read_bag<PossibleFlagsT>(&self) -> BitBag<PossibleFlagsT>
+    where
+        PossibleFlagsT: BitBaggable<ReprT = Self::T>,
+    {
+        BitBag::new(self.get())
+    }
+}
+
+impl<RegisterT: Readable + ?Sized> ReadBag for RegisterT {}
+
+/// Write a [`BitBag`] to a register
+pub trait WriteBag: Writeable {
+    fn write_bag<PossibleFlagsT>(&self, bag: BitBag<PossibleFlagsT>)
+    where
+        PossibleFlagsT: BitBaggable<ReprT = Self::T>,
+    {
+        self.set(bag.repr)
+    }
+}
+
+impl<RegisterT: Writeable + ?Sized> WriteBag for RegisterT {}
+
+/// Read, modify and write a register as a [`BitBag`]
+pub trait ModifyBag: Readable + Writeable<T = <Self as Readable>::T> {
+    /// Read the register once, apply `f`, and write it back once,
+    /// keeping the bits which aren't defined in the enum as they were read, as [`BitBag::modify`] does
+    fn modify_bag<PossibleFlagsT>(
+        &self,
+        f: impl FnOnce(&mut BitBag<PossibleFlagsT>) -> &mut BitBag<PossibleFlagsT>,
+    ) where
+        PossibleFlagsT: BitBaggable<ReprT = <Self as Readable>::T>,
+    {
+        let mut bag = self.read_bag();
+        bag.modify(f);
+        self.write_bag(bag)
+    }
+}
+
+impl<RegisterT> ModifyBag for RegisterT where
+    RegisterT: Readable + Writeable<T = <RegisterT as Readable>::T> + ?Sized
+{
+}
+
+impl<ReprT, NameT> FlagRegister for ReadWrite<ReprT, NameT>
+where
+    ReprT: UIntLike + PrimInt,
+    NameT: RegisterLongName,
+{
+    type ReprT = ReprT;
+
+    fn read_repr(&self) -> ReprT {
+        self.get()
+    }
+
+    fn write_repr(&self, repr: ReprT) {
+        self.set(repr)
+    }
+}
+
+impl<ReprT, NameT> FlagRegister for InMemoryRegister<ReprT, NameT>
+where
+    ReprT: UIntLike + PrimInt,
+    NameT: RegisterLongName,
+{
+    type ReprT = ReprT;
+
+    fn read_repr(&self) -> ReprT {
+        self.get()
+    }
+
+    fn write_repr(&self, repr: ReprT) {
+        self.set(repr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModifyBag as _, ReadBag as _, WriteBag as _};
+    use crate::tests::FooFlags::{self, *};
+    use crate::{BitBag, FlagRegister};
+    use ::tock_registers::{
+        interfaces::{Readable as _, Writeable as _},
+        registers::InMemoryRegister,
+    };
+
+    #[test]
+    fn register() {
+        let register = InMemoryRegister::<u8>::new(0b1000_0000);
+        assert_eq!(register.read_bag::<FooFlags>(), BitBag::new(0b1000_0000));
+        register.write_bag(A | B);
+        assert_eq!(register.get(), 0b0011);
+        register.set(0b1000_0011);
+        register.modify_bag::<FooFlags>(|bag| bag.clear_all().set(D));
+        assert_eq!(register.get(), 0b1000_1000);
+    }
+
+    #[test]
+    fn flag_register() {
+        let register = InMemoryRegister::<u8>::new(0b1000_0000);
+        FlagRegister::write_flags(&register, B | C);
+        assert_eq!(register.get(), 0b0110);
+    }
+}
@@ -0,0 +1,126 @@
+//! A macro exposing bags through [UniFFI](https://docs.rs/uniffi), enabled by the `uniffi` feature.
+//!
+//! UniFFI only accepts custom types named by a single identifier, and declared in the crate which calls `setup_scaffolding!`,
+//! so [`uniffi_bag!`](crate::uniffi_bag) declares a type alias for the bag, and registers it as a custom type there.
+//! A bag can be passed either as its bits, in a `u64`, or as a sequence of the enum, which must also derive `Clone` and `uniffi::Enum`.
+//! Bags received from foreign code are checked, and rejected if they have unrecognised bits.
+//! ```ignore
+//! uniffi::setup_scaffolding!();
+//!
+//! #[derive(Debug, Clone, Copy, BitBaggable, BitOr, uniffi::Enum)]
+//! #[repr(u8)]
+//! pub enum Perm {
+//!     Read = 0b0001,
+//!     Write = 0b0010,
+//! }
+//!
+//! bitbag::uniffi_bag! {
+//!     /// Passed to Kotlin and Swift as a list of `Perm`
+//!     pub type Perms = BitBag<Perm> as Vec<Perm>;
+//! }
+//!
+//! #[uniffi::export]
+//! fn can_write(perms: Perms) -> bool {
+//!     perms.is_set(Perm::Write)
+//! }
+//! ```
+
+/// Declare a type alias for a bag, and register it with UniFFI as a custom type, see the [module docs](crate::uniffi).
+///
+/// The alias is lowered `as u64` (the bits) or `as Vec<Enum>` (the set flags).
+/// Each enum can only be registered once, as the aliases are all the same type.
+/// ```ignore
+/// bitbag::uniffi_bag! {
+///     pub type Perms = BitBag<Perm> as u64;
+///     pub type Modes = BitBag<Mode> as Vec<Mode>;
+/// }
+/// ```
+#[macro_export]
+macro_rules! uniffi_bag {
+    () => {};
+    ($(#[$meta:meta])* $vis:vis type $alias:ident = BitBag<$flags:ty> as u64; $($rest:tt)*) => {
+        $(#[$meta])*
+        $vis type $alias = $crate::BitBag<$flags>;
+
+        ::uniffi::custom_type!($alias, u64, {
+            remote,
+            lower: |bag| bag.get() as u64,
+            try_lift: |bits| {
+                let repr = bits as <$flags as $crate::BitBaggable>::ReprT;
+                if repr as u64 != bits {
+                    ::uniffi::deps::anyhow::bail!("{:#x} is too wide for {}", bits, ::core::any::type_name::<$flags>());
+                }
+                $crate::BitBag::new_strict(repr).map_err(|e| ::uniffi::deps::anyhow::anyhow!("{}", e))
+            },
+        });
+        $crate::uniffi_bag!($($rest)*);
+    };
+    ($(#[$meta:meta])* $vis:vis type $alias:ident = BitBag<$flags:ty> as Vec<$element:ty>; $($rest:tt)*) => {
+        $(#[$meta])*
+        $vis type $alias = $crate::BitBag<$flags>;
+
+        ::uniffi::custom_type!($alias, ::std::vec::Vec<$element>, {
+            remote,
+            lower: |bag| bag.into_iter().collect(),
+            try_lift: |flags| {
+                let mut bag = $crate::BitBag::empty();
+                for flag in flags {
+                    bag.set(flag);
+                }
+                ::core::result::Result::Ok(bag)
+            },
+        });
+        $crate::uniffi_bag!($($rest)*);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::{BitBag, BitBaggable, BitOr};
+    use ::uniffi::{FfiConverter, Lift, Lower};
+
+    #[derive(Debug, Clone, Copy, PartialEq, BitBaggable, BitOr, ::uniffi::Enum)]
+    #[repr(u16)]
+    pub enum Perm {
+        Read = 0b0001,
+        Write = 0b0010,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, BitBaggable, BitOr)]
+    #[repr(u8)]
+    pub enum Mode {
+        Fast = 0b0001,
+        Safe = 0b1000,
+    }
+
+    crate::uniffi_bag! {
+        pub type Perms = BitBag<Perm> as Vec<Perm>;
+        /// Lowered as bits
+        pub type Modes = BitBag<Mode> as u64;
+    }
+
+    type Tag = crate::UniFfiTag;
+
+    #[test]
+    fn sequence() {
+        let bag: Perms = Perm::Read | Perm::Write;
+        let lowered = <Perms as Lower<Tag>>::lower(bag);
+        assert_eq!(<Perms as Lift<Tag>>::try_lift(lowered).unwrap(), bag);
+
+        let flags = <Vec<Perm> as Lower<Tag>>::lower(vec![Perm::Write]);
+        assert_eq!(
+            <Perms as FfiConverter<Tag>>::try_lift(flags).unwrap(),
+            BitBag::just(Perm::Write)
+        );
+    }
+
+    #[test]
+    fn bits() {
+        let bag: Modes = Mode::Fast | Mode::Safe;
+        assert_eq!(<Modes as Lower<Tag>>::lower(bag), 0b1001);
+        assert_eq!(<Modes as Lift<Tag>>::try_lift(0b1001).unwrap(), bag);
+        assert!(<Modes as Lift<Tag>>::try_lift(0b0010).is_err());
+        assert!(<Modes as Lift<Tag>>::try_lift(0x100).is_err());
+    }
+}
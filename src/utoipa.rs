@@ -0,0 +1,61 @@
+//! [utoipa](https://docs.rs/utoipa) support, enabled by the `utoipa` feature.
+//!
+//! The schema matches the human-readable serde representation: an array of unique flag names,
+//! or an integer if [`BitBaggable::ENCODING`] is [`Encoding::Bits`].
+//! The schema is named after the full path of the enum, like `BitBag_my_crate_Flags`.
+
+use crate::{schema_name, BitBag, BitBaggable, Encoding};
+use ::utoipa::{
+    openapi::{
+        schema::{ArrayBuilder, ObjectBuilder, Schema, Type},
+        RefOr,
+    },
+    PartialSchema, ToSchema,
+};
+use std::borrow::Cow;
+
+impl<PossibleFlagsT: BitBaggable> PartialSchema for BitBag<PossibleFlagsT> {
+    fn schema() -> RefOr<Schema> {
+        if let Encoding::Bits = PossibleFlagsT::ENCODING {
+            return ObjectBuilder::new().schema_type(Type::Integer).into();
+        }
+        let names = PossibleFlagsT::VARIANTS.iter().map(|(name, _, _)| *name);
+        ArrayBuilder::new()
+            .items(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(names)),
+            )
+            .unique_items(true)
+            .into()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> ToSchema for BitBag<PossibleFlagsT> {
+    fn name() -> Cow<'static, str> {
+        Cow::Owned(schema_name::<PossibleFlagsT>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::FooFlags;
+    use crate::BitBag;
+    use ::utoipa::{PartialSchema as _, ToSchema as _};
+
+    #[test]
+    fn schema() {
+        assert_eq!(BitBag::<FooFlags>::name(), "BitBag_bitbag_tests_FooFlags");
+        assert_eq!(
+            serde_json::to_value(BitBag::<FooFlags>::schema()).unwrap(),
+            serde_json::json!({
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "enum": ["A", "B", "C", "D"],
+                },
+                "uniqueItems": true,
+            })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! validator support, enabled by the `validator` feature.
+//!
+//! [`MissingFlags`] and [`ForbiddenFlags`] convert to a [`ValidationError`], so policy checks can be `?`ed in a
+//! `#[validate(custom(function = ...))]` function. The flag names are kept in the error's `missing` or `forbidden`
+//! param.
+
+use crate::{BitBag, BitBaggable, ForbiddenFlags, MissingFlags};
+use ::validator::ValidationError;
+use std::borrow::Cow;
+
+fn named<PossibleFlagsT: BitBaggable>(
+    code: &'static str,
+    message: String,
+    param: &'static str,
+    bag: BitBag<PossibleFlagsT>,
+) -> ValidationError {
+    let mut e = ValidationError::new(code).with_message(Cow::Owned(message));
+    let names: Vec<&str> = bag.iter_names().collect();
+    e.add_param(Cow::Borrowed(param), &names);
+    e
+}
+
+impl<PossibleFlagsT: BitBaggable> From<MissingFlags<PossibleFlagsT>> for ValidationError {
+    fn from(e: MissingFlags<PossibleFlagsT>) -> Self {
+        named("missing_flags", e.to_string(), "missing", e.missing())
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> From<ForbiddenFlags<PossibleFlagsT>> for ValidationError {
+    fn from(e: ForbiddenFlags<PossibleFlagsT>) -> Self {
+        named("forbidden_flags", e.to_string(), "forbidden", e.forbidden())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    fn policy(bag: &BitBag<FooFlags>) -> Result<(), ValidationError> {
+        bag.require_all(A | B)?;
+        bag.forbid(C | D)?;
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(policy(&(A | B)), Ok(()));
+
+        let e = policy(&BitBag::just(B)).unwrap_err();
+        assert_eq!(e.code, "missing_flags");
+        assert_eq!(e.message.as_deref(), Some("missing required flags: A"));
+        assert_eq!(e.params["missing"], ::serde_json::json!(["A"]));
+
+        let e = policy(&(A | B | C | D)).unwrap_err();
+        assert_eq!(e.code, "forbidden_flags");
+        assert_eq!(e.params["forbidden"], ::serde_json::json!(["C", "D"]));
+    }
+}
@@ -0,0 +1,58 @@
+//! [valuable](https://docs.rs/valuable) support, enabled by the `valuable` feature.
+//!
+//! A [`BitBag`] is a [`Listable`] of the names of its set flags, leaving out any unrecognised bits,
+//! so subscribers which understand valuable, like tracing's `valuable` support, can index on individual flags.
+
+use crate::{BitBag, BitBaggable};
+use ::valuable::{Listable, Valuable, Value, Visit};
+
+impl<PossibleFlagsT: BitBaggable> Valuable for BitBag<PossibleFlagsT> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Listable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        for name in self.iter_names() {
+            visit.visit_value(Value::String(name));
+        }
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Listable for BitBag<PossibleFlagsT> {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter_names().count();
+        (len, Some(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::{self, *};
+
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl Visit for Names {
+        fn visit_value(&mut self, value: Value<'_>) {
+            match value {
+                Value::Listable(list) => list.visit(self),
+                Value::String(name) => self.0.push(name.to_string()),
+                other => panic!("unexpected value {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn names() {
+        let bag = A | C;
+        let mut names = Names::default();
+        ::valuable::visit(&bag, &mut names);
+        assert_eq!(names.0, ["A", "C"]);
+        assert_eq!(Listable::size_hint(&bag), (2, Some(2)));
+
+        let mut names = Names::default();
+        ::valuable::visit(&BitBag::<FooFlags>::new(0b1_0000), &mut names);
+        assert!(names.0.is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+//! [Kani](https://model-checking.github.io/kani/) proof harnesses for the set laws, built by `cargo kani`.
+//!
+//! Unlike the tests, each harness checks its law for every possible primitive and flag,
+//! so downstream safety cases can cite the proofs.
+
+use crate as bitbag;
+use crate::{BitBag, BitBaggable};
+
+/// Gaps between the bits, so masking has something to clear
+#[derive(Debug, Clone, Copy, BitBaggable)]
+#[repr(u16)]
+enum Sparse {
+    Low = 0x0001,
+    Mid = 0x0010,
+    High = 0x8000,
+}
+
+fn any_bag() -> BitBag<Sparse> {
+    BitBag::new(kani::any())
+}
+
+fn any_flag() -> Sparse {
+    let ix: usize = kani::any();
+    kani::assume(ix < Sparse::VARIANTS.len());
+    Sparse::VARIANTS[ix].1
+}
+
+/// Masking a masked bag changes nothing, and leaves no unrecognised bits
+#[kani::proof]
+fn new_masked_idempotent() {
+    let masked = BitBag::<Sparse>::new_masked(kani::any());
+    assert_eq!(BitBag::<Sparse>::new_masked(masked.get()), masked);
+    assert!(!masked.has_unrecognised_bits());
+}
+
+/// [`BitBag::new_strict`] accepts exactly the primitives which masking leaves unchanged
+#[kani::proof]
+fn new_strict_agrees_with_masking() {
+    let prim: u16 = kani::any();
+    let unchanged = BitBag::<Sparse>::new_masked(prim).get() == prim;
+    assert_eq!(BitBag::<Sparse>::new_strict(prim).is_ok(), unchanged);
+}
+
+/// A flag is set after setting it, and unset after unsetting it, whatever else the bag holds
+#[kani::proof]
+fn set_then_contains() {
+    let mut bag = any_bag();
+    let flag = any_flag();
+    bag.set(flag);
+    assert!(bag.is_set(flag));
+    assert!(bag.contains_all(BitBag::just(flag)));
+    bag.unset(flag);
+    assert!(!bag.is_set(flag));
+}
+
+/// Within the mask, complementing twice is the identity, and a bag and its complement partition the mask
+#[kani::proof]
+fn complement_involutive() {
+    let bag = any_bag();
+    let all = BitBag::<Sparse>::all();
+    assert_eq!(!!bag & all, bag & all);
+    assert_eq!((!bag & all) | (bag & all), all);
+    assert!((!bag & bag).is_empty());
+}
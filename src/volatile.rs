@@ -0,0 +1,107 @@
+//! Typed access to memory-mapped flag registers, see [`VolatileBitBag`].
+
+use crate::{BitBag, BitBaggable};
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ptr,
+};
+
+/// A pointer to a register holding a [`BitBag`], which is only ever accessed with volatile reads and writes.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, BitOr, VolatileBitBag};
+/// #[derive(Debug, BitBaggable, BitOr)]
+/// #[repr(u32)]
+/// enum Status {
+///     Ready = 0b01,
+///     Error = 0b10,
+/// }
+///
+/// # let mut register = 0u32;
+/// # let address = &mut register as *mut u32 as usize;
+/// // let address = 0x4000_1000;
+/// let status = unsafe { VolatileBitBag::<Status>::new(address as *mut u32) };
+/// status.write(Status::Ready | Status::Error);
+/// status.modify(|bag| bag.unset(Status::Error));
+/// assert!(!status.read().is_set(Status::Error));
+/// ```
+pub struct VolatileBitBag<PossibleFlagsT: BitBaggable> {
+    ptr: *mut PossibleFlagsT::ReprT,
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> VolatileBitBag<PossibleFlagsT> {
+    /// # Safety
+    /// `ptr` must be valid for volatile reads and writes, and properly aligned,
+    /// for as long as this (or any copy of it) is used.
+    pub const unsafe fn new(ptr: *mut PossibleFlagsT::ReprT) -> Self {
+        Self {
+            ptr,
+            _flags: PhantomData,
+        }
+    }
+
+    pub const fn as_ptr(&self) -> *mut PossibleFlagsT::ReprT {
+        self.ptr
+    }
+
+    pub fn read(&self) -> BitBag<PossibleFlagsT> {
+        // Safety: guaranteed by the caller of `new`
+        BitBag::new(unsafe { ptr::read_volatile(self.ptr) })
+    }
+
+    pub fn write(&self, bag: BitBag<PossibleFlagsT>) {
+        // Safety: guaranteed by the caller of `new`
+        unsafe { ptr::write_volatile(self.ptr, bag.repr) }
+    }
+
+    /// Read the register once, apply `f`, and write it back once,
+    /// keeping the bits which aren't defined in the enum as they were read, as [`BitBag::modify`] does
+    pub fn modify(
+        &self,
+        f: impl FnOnce(&mut BitBag<PossibleFlagsT>) -> &mut BitBag<PossibleFlagsT>,
+    ) {
+        let mut bag = self.read();
+        bag.modify(f);
+        self.write(bag)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for VolatileBitBag<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for VolatileBitBag<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> Debug for VolatileBitBag<PossibleFlagsT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VolatileBitBag").field(&self.ptr).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VolatileBitBag;
+    use crate::tests::FooFlags::{self, *};
+    use crate::BitBag;
+
+    #[test]
+    fn read_write() {
+        let mut register = 0b1000_0001u8;
+        let volatile = unsafe { VolatileBitBag::<FooFlags>::new(&mut register) };
+        assert_eq!(volatile.read(), BitBag::new(0b1000_0001));
+        volatile.write(B | C);
+        assert_eq!(volatile.read(), B | C);
+        assert_eq!(register, 0b0110);
+    }
+
+    #[test]
+    fn modify() {
+        let mut register = 0b1010_0101u8;
+        let volatile = unsafe { VolatileBitBag::<FooFlags>::new(&mut register) };
+        volatile.modify(|bag| bag.clear_all().set(B));
+        assert_eq!(register, 0b1010_0010);
+    }
+}
@@ -0,0 +1,88 @@
+//! [wasm-bindgen](https://docs.rs/wasm-bindgen) support, enabled by the `wasm-bindgen` feature.
+//!
+//! A [`BitBag`] converts to and from a [`JsValue`] as its inner primitive does:
+//! a number for reprs up to 32 bits, and a `BigInt` for wider ones.
+//! Converting from JS permits (and preserves) unrecognised bits, as [`BitBag::new`] does.
+//!
+//! [`to_names`] and [`from_names`] instead use an array of flag names.
+//! ```no_run
+//! use bitbag::{BitBag, BitBaggable, BitOr};
+//! use std::convert::TryFrom;
+//! use wasm_bindgen::JsValue;
+//!
+//! #[derive(Debug, BitBaggable, BitOr)]
+//! #[repr(u8)]
+//! enum Flags {
+//!     A = 0b0001,
+//!     B = 0b0010,
+//! }
+//!
+//! let value = JsValue::from(Flags::A | Flags::B);
+//! assert_eq!(BitBag::<Flags>::try_from(value).unwrap(), Flags::A | Flags::B);
+//!
+//! let names = bitbag::wasm_bindgen::to_names(Flags::A | Flags::B);
+//! assert_eq!(bitbag::wasm_bindgen::from_names::<Flags>(names).unwrap(), Flags::A | Flags::B);
+//! ```
+
+use crate::{parse::by_name, BitBag, BitBaggable, ParseError};
+use ::wasm_bindgen::{convert::TryFromJsValue, JsError, JsValue};
+use std::{convert::TryFrom, fmt::Binary};
+
+impl<PossibleFlagsT: BitBaggable> From<BitBag<PossibleFlagsT>> for JsValue
+where
+    PossibleFlagsT::ReprT: Into<JsValue>,
+{
+    fn from(bag: BitBag<PossibleFlagsT>) -> Self {
+        bag.repr.into()
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> TryFromJsValue for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: TryFromJsValue,
+{
+    fn try_from_js_value_ref(value: &JsValue) -> Option<Self> {
+        PossibleFlagsT::ReprT::try_from_js_value_ref(value).map(BitBag::new)
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> TryFrom<JsValue> for BitBag<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: TryFromJsValue,
+{
+    type Error = JsValue;
+
+    fn try_from(value: JsValue) -> Result<Self, JsValue> {
+        Self::try_from_js_value(value)
+    }
+}
+
+/// An array of the names of the flags set in `bag`, leaving out any unrecognised bits.
+pub fn to_names<PossibleFlagsT: BitBaggable>(bag: BitBag<PossibleFlagsT>) -> JsValue {
+    let names: Vec<String> = bag.iter_names().map(str::to_string).collect();
+    names.into()
+}
+
+/// Read a bag from an array of flag names.
+///
+/// Fails with a JS `Error` if `value` isn't an array of strings, or on the first name which isn't defined in the enum.
+pub fn from_names<PossibleFlagsT: BitBaggable>(
+    value: JsValue,
+) -> Result<BitBag<PossibleFlagsT>, JsValue>
+where
+    PossibleFlagsT::ReprT: Binary,
+{
+    let names = Vec::<String>::try_from_js_value(value)
+        .map_err(|_| JsError::new("expected an array of flag names"))?;
+    let mut bag = BitBag::empty();
+    for name in names {
+        match by_name::<PossibleFlagsT>(&name) {
+            Some(repr) => bag.set_raw(repr),
+            None => {
+                let e = ParseError::<PossibleFlagsT>::UnknownName(name);
+                return Err(JsError::new(&e.to_string()).into());
+            }
+        };
+    }
+    Ok(bag)
+}
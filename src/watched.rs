@@ -0,0 +1,176 @@
+//! A bag which reports every change to a callback, see [`WatchedBitBag`].
+
+use crate::{BitBag, BitBaggable};
+use core::fmt::{self, Debug};
+
+/// A [`BitBag`] which calls `on_change` with a [`Change`] whenever a modification actually changes its bits.
+///
+/// To push changes to a channel instead, send them from the callback.
+/// ```
+/// # use bitbag::{BitBag, BitBaggable, BitOr, WatchedBitBag};
+/// # #[derive(Clone, BitBaggable, BitOr)]
+/// # #[repr(u8)]
+/// # enum Setting {
+/// #     Verbose = 0b01,
+/// #     Colour = 0b10,
+/// # }
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// let mut settings = WatchedBitBag::new(BitBag::just(Setting::Colour), move |change| {
+///     tx.send(change).unwrap();
+/// });
+///
+/// settings.set(Setting::Verbose);
+/// settings.set(Setting::Verbose); // No change, so no notification
+/// settings.unset(Setting::Colour);
+///
+/// let changes = rx.try_iter().collect::<Vec<_>>();
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!(changes[0].added(), BitBag::just(Setting::Verbose));
+/// assert_eq!(changes[1].removed(), BitBag::just(Setting::Colour));
+/// ```
+pub struct WatchedBitBag<PossibleFlagsT: BitBaggable, OnChangeT: FnMut(Change<PossibleFlagsT>)> {
+    bag: BitBag<PossibleFlagsT>,
+    on_change: OnChangeT,
+}
+
+impl<PossibleFlagsT: BitBaggable, OnChangeT: FnMut(Change<PossibleFlagsT>)>
+    WatchedBitBag<PossibleFlagsT, OnChangeT>
+{
+    /// Watch `bag`, which doesn't itself notify
+    pub fn new(bag: BitBag<PossibleFlagsT>, on_change: OnChangeT) -> Self {
+        Self { bag, on_change }
+    }
+
+    pub fn get(&self) -> BitBag<PossibleFlagsT> {
+        self.bag
+    }
+
+    /// Stop watching, returning the bag
+    pub fn into_inner(self) -> BitBag<PossibleFlagsT> {
+        self.bag
+    }
+
+    pub fn set(&mut self, flag: PossibleFlagsT) -> &mut Self {
+        self.modify(|bag| bag.set(flag))
+    }
+
+    pub fn unset(&mut self, flag: PossibleFlagsT) -> &mut Self {
+        self.modify(|bag| bag.unset(flag))
+    }
+
+    /// Replace the whole bag, returning the previous one
+    pub fn replace(&mut self, bag: BitBag<PossibleFlagsT>) -> BitBag<PossibleFlagsT> {
+        let before = self.bag;
+        self.modify(|current| {
+            *current = bag;
+            current
+        });
+        before
+    }
+
+    /// Apply `f` to the bag, notifying once for the combined change
+    pub fn modify(
+        &mut self,
+        f: impl FnOnce(&mut BitBag<PossibleFlagsT>) -> &mut BitBag<PossibleFlagsT>,
+    ) -> &mut Self {
+        let before = self.bag;
+        f(&mut self.bag);
+        if self.bag != before {
+            (self.on_change)(Change {
+                before,
+                after: self.bag,
+            });
+        }
+        self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable, OnChangeT: FnMut(Change<PossibleFlagsT>)> Debug
+    for WatchedBitBag<PossibleFlagsT, OnChangeT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedBitBag")
+            .field("bag", &self.bag)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A modification of a [`WatchedBitBag`]
+pub struct Change<PossibleFlagsT: BitBaggable> {
+    /// The bag as it was
+    pub before: BitBag<PossibleFlagsT>,
+    /// The bag as it is now
+    pub after: BitBag<PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: BitBaggable> Change<PossibleFlagsT> {
+    /// The bits which were set by the change
+    pub fn added(&self) -> BitBag<PossibleFlagsT> {
+        self.after & !self.before
+    }
+
+    /// The bits which were cleared by the change
+    pub fn removed(&self) -> BitBag<PossibleFlagsT> {
+        self.before & !self.after
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Clone for Change<PossibleFlagsT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Copy for Change<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> PartialEq for Change<PossibleFlagsT> {
+    fn eq(&self, other: &Self) -> bool {
+        self.before == other.before && self.after == other.after
+    }
+}
+
+impl<PossibleFlagsT: BitBaggable> Eq for Change<PossibleFlagsT> {}
+
+impl<PossibleFlagsT: BitBaggable> Debug for Change<PossibleFlagsT>
+where
+    PossibleFlagsT::ReprT: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Change")
+            .field("before", &self.before)
+            .field("after", &self.after)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FooFlags::*;
+
+    #[test]
+    fn notifies_on_change() {
+        let mut changes = Vec::new();
+        let mut bag = WatchedBitBag::new(A | B, |change| changes.push(change));
+        bag.set(C).set(A).unset(D);
+        bag.modify(|bag| bag.unset(A).set(D));
+        assert_eq!(bag.replace(BitBag::empty()), B | C | D);
+        bag.replace(BitBag::empty());
+        assert!(bag.into_inner().is_empty());
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].added(), BitBag::just(C));
+        assert!(changes[0].removed().is_empty());
+        assert_eq!(changes[1].added(), BitBag::just(D));
+        assert_eq!(changes[1].removed(), BitBag::just(A));
+        assert_eq!(
+            changes[2],
+            Change {
+                before: B | C | D,
+                after: BitBag::empty()
+            }
+        );
+    }
+}
@@ -0,0 +1,639 @@
+//! Bags of more flags than fit in a primitive, see [`WideBitBag`].
+
+use crate::BufferTooShort;
+use core::{
+    any::type_name,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not},
+};
+
+/// The trait that allows an enum to be placed inside a [`WideBitBag`].
+///
+/// Unlike [`BitBaggable`](crate::BitBaggable), each variant's discriminant is the index of its bit, rather than a mask.
+/// You should derive this with the `WideBitBaggable` derive macro.
+pub trait WideBitBaggable: Sized + 'static {
+    /// The index of this variant's bit
+    fn bit(self) -> usize;
+    /// names, values and bit indices
+    const VARIANTS: &'static [(&'static str, Self, usize)];
+    /// One more than the highest bit index
+    const BITS: usize = bits_of(Self::VARIANTS);
+}
+
+const fn bits_of<PossibleFlagsT>(variants: &[(&str, PossibleFlagsT, usize)]) -> usize {
+    let mut bits = 0;
+    let mut ix = 0;
+    while ix < variants.len() {
+        if variants[ix].2 >= bits {
+            bits = variants[ix].2 + 1;
+        }
+        ix += 1;
+    }
+    bits
+}
+
+/// Like a [`BitBag`](crate::BitBag), but backed by `N` little-endian `u64` words, for enums with more than 128 flags.
+///
+/// `N` must be large enough for every bit in the enum, which is checked at compile time.
+/// ```
+/// # use bitbag::{WideBitBag, WideBitBaggable};
+/// #[derive(Debug, Clone, Copy, WideBitBaggable)]
+/// #[repr(u16)]
+/// enum Component {
+///     Position = 0,
+///     Velocity = 1,
+///     Sprite = 200,
+/// }
+///
+/// let mut archetype = WideBitBag::<Component, 4>::empty();
+/// archetype.set(Component::Position).set(Component::Sprite);
+/// assert!(archetype.is_set(Component::Sprite));
+/// assert_eq!(archetype.get(), [1, 0, 0, 1 << 8]);
+/// assert_eq!(archetype.to_string(), "Position | Sprite");
+/// ```
+#[repr(transparent)]
+pub struct WideBitBag<PossibleFlagsT: WideBitBaggable, const N: usize> {
+    pub words: [u64; N],
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+/// Constructors
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> WideBitBag<PossibleFlagsT, N> {
+    const FITS: () = assert!(
+        PossibleFlagsT::BITS <= N * 64,
+        "the enum has bits beyond the end of the WideBitBag"
+    );
+
+    /// New bag, permitting (and preserving) unrecognised bits
+    pub const fn new(words: [u64; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS;
+        Self {
+            words,
+            _flags: PhantomData,
+        }
+    }
+
+    /// New bag with no bits set
+    pub const fn empty() -> Self {
+        Self::new([0; N])
+    }
+
+    /// New bag with all defined bits set
+    pub fn all() -> Self {
+        Self::new(mask::<PossibleFlagsT, N>())
+    }
+
+    /// New bag, discarding any bits which aren't defined in the enum
+    pub fn new_masked(words: [u64; N]) -> Self {
+        let mut bag = Self::new(words);
+        bag.words = and(bag.words, mask::<PossibleFlagsT, N>());
+        bag
+    }
+
+    /// Check the bits of `words`, and return a [`WideNonFlagBits`] error if it has bits set which aren't defined in the enum.
+    pub fn new_strict(words: [u64; N]) -> Result<Self, WideNonFlagBits<PossibleFlagsT, N>> {
+        let bag = Self::new(words);
+        match bag.unrecognised_bits() {
+            Some(unrecognised) => Err(WideNonFlagBits {
+                unrecognised,
+                _flags: PhantomData,
+            }),
+            None => Ok(bag),
+        }
+    }
+}
+
+/// Accessors
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> WideBitBag<PossibleFlagsT, N> {
+    /// Get a copy of the words
+    pub const fn get(&self) -> [u64; N] {
+        self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn is_set(&self, flag: PossibleFlagsT) -> bool {
+        let (word, bit) = locate(flag.bit());
+        self.words[word] & bit != 0
+    }
+
+    pub fn unrecognised_bits(&self) -> Option<[u64; N]> {
+        let unrecognised = and(self.words, not(mask::<PossibleFlagsT, N>()));
+        match unrecognised.iter().all(|word| *word == 0) {
+            true => None,
+            false => Some(unrecognised),
+        }
+    }
+
+    pub fn has_unrecognised_bits(&self) -> bool {
+        self.unrecognised_bits().is_some()
+    }
+
+    /// The number of bits set, including any unrecognised bits
+    pub fn count_ones(&self) -> u32 {
+        lanes::count_ones(&self.words)
+    }
+
+    /// Whether every flag set in `other` is also set in `self`
+    pub fn contains(&self, other: &Self) -> bool {
+        and(self.words, other.words) == other.words
+    }
+
+    /// Iterate over the set flags, in declaration order
+    pub fn iter(&self) -> impl Iterator<Item = PossibleFlagsT> + '_
+    where
+        PossibleFlagsT: Clone,
+    {
+        PossibleFlagsT::VARIANTS
+            .iter()
+            .filter(move |(_, value, _)| self.is_set(value.clone()))
+            .map(|(_, value, _)| value.clone())
+    }
+}
+
+/// Builder
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> WideBitBag<PossibleFlagsT, N> {
+    pub fn set(&mut self, flag: PossibleFlagsT) -> &mut Self {
+        let (word, bit) = locate(flag.bit());
+        self.words[word] |= bit;
+        self
+    }
+
+    pub fn unset(&mut self, flag: PossibleFlagsT) -> &mut Self {
+        let (word, bit) = locate(flag.bit());
+        self.words[word] &= !bit;
+        self
+    }
+
+    pub fn set_all(&mut self) -> &mut Self {
+        for (word, mask) in self.words.iter_mut().zip(mask::<PossibleFlagsT, N>()) {
+            *word |= mask;
+        }
+        self
+    }
+
+    pub fn clear_all(&mut self) -> &mut Self {
+        self.words = [0; N];
+        self
+    }
+}
+
+/// Byte encodings
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> WideBitBag<PossibleFlagsT, N> {
+    /// The number of bytes written by [`WideBitBag::encode_into`]
+    pub const ENCODED_LEN: usize = N * 8;
+
+    /// Write the words into the start of `buf`, each little-endian, lowest word first, returning the number of bytes written
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, BufferTooShort> {
+        let available = buf.len();
+        let buf = buf
+            .get_mut(..Self::ENCODED_LEN)
+            .ok_or_else(|| BufferTooShort::new(Self::ENCODED_LEN, available))?;
+        for (chunk, word) in buf.chunks_exact_mut(8).zip(self.words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(Self::ENCODED_LEN)
+    }
+
+    /// Read a bag from the start of `buf`, as written by [`WideBitBag::encode_into`].
+    ///
+    /// Any bytes after the first [`WideBitBag::ENCODED_LEN`] are ignored.
+    /// Fails if there are bits set which aren't defined in the enum.
+    pub fn decode_from(buf: &[u8]) -> Result<Self, WideDecodeError<PossibleFlagsT, N>> {
+        let too_short = BufferTooShort::new(Self::ENCODED_LEN, buf.len());
+        let buf = buf
+            .get(..Self::ENCODED_LEN)
+            .ok_or(WideDecodeError::BufferTooShort(too_short))?;
+        let mut words = [0; N];
+        for (word, chunk) in words.iter_mut().zip(buf.chunks_exact(8)) {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(chunk);
+            *word = u64::from_le_bytes(bytes);
+        }
+        Self::new_strict(words).map_err(WideDecodeError::NonFlagBits)
+    }
+}
+
+fn locate(bit: usize) -> (usize, u64) {
+    (bit / 64, 1 << (bit % 64))
+}
+
+fn mask<PossibleFlagsT: WideBitBaggable, const N: usize>() -> [u64; N] {
+    let mut mask = [0; N];
+    for (_, _, bit) in PossibleFlagsT::VARIANTS {
+        let (word, bit) = locate(*bit);
+        mask[word] |= bit;
+    }
+    mask
+}
+
+fn and<const N: usize>(left: [u64; N], right: [u64; N]) -> [u64; N] {
+    lanes::combine(left, right, lanes::Op::And)
+}
+
+fn or<const N: usize>(left: [u64; N], right: [u64; N]) -> [u64; N] {
+    lanes::combine(left, right, lanes::Op::Or)
+}
+
+fn not<const N: usize>(words: [u64; N]) -> [u64; N] {
+    lanes::not(words)
+}
+
+/// Word-wise operations, eight words at a time with `core::simd` when the `simd` feature is enabled,
+/// falling back to one word at a time for the remainder.
+mod lanes {
+    #[cfg(feature = "simd")]
+    use core::simd::{num::SimdUint as _, Simd};
+
+    #[cfg(feature = "simd")]
+    const LANES: usize = 8;
+
+    #[derive(Clone, Copy)]
+    pub(super) enum Op {
+        And,
+        Or,
+    }
+
+    pub(super) fn combine<const N: usize>(mut left: [u64; N], right: [u64; N], op: Op) -> [u64; N] {
+        #[cfg(feature = "simd")]
+        let done = {
+            for (left, right) in left.chunks_exact_mut(LANES).zip(right.chunks_exact(LANES)) {
+                let (l, r) = (
+                    Simd::<u64, LANES>::from_slice(left),
+                    Simd::from_slice(right),
+                );
+                let out = match op {
+                    Op::And => l & r,
+                    Op::Or => l | r,
+                };
+                out.copy_to_slice(left);
+            }
+            N - N % LANES
+        };
+        #[cfg(not(feature = "simd"))]
+        let done = 0;
+        for (left, right) in left[done..].iter_mut().zip(&right[done..]) {
+            match op {
+                Op::And => *left &= right,
+                Op::Or => *left |= right,
+            }
+        }
+        left
+    }
+
+    pub(super) fn not<const N: usize>(mut words: [u64; N]) -> [u64; N] {
+        #[cfg(feature = "simd")]
+        let done = {
+            for chunk in words.chunks_exact_mut(LANES) {
+                (!Simd::<u64, LANES>::from_slice(chunk)).copy_to_slice(chunk);
+            }
+            N - N % LANES
+        };
+        #[cfg(not(feature = "simd"))]
+        let done = 0;
+        for word in &mut words[done..] {
+            *word = !*word;
+        }
+        words
+    }
+
+    pub(super) fn count_ones<const N: usize>(words: &[u64; N]) -> u32 {
+        #[cfg(feature = "simd")]
+        let (mut count, done) = {
+            let count = words
+                .chunks_exact(LANES)
+                .map(|chunk| {
+                    Simd::<u64, LANES>::from_slice(chunk)
+                        .count_ones()
+                        .reduce_sum() as u32
+                })
+                .sum::<u32>();
+            (count, N - N % LANES)
+        };
+        #[cfg(not(feature = "simd"))]
+        let (mut count, done) = (0, 0);
+        for word in &words[done..] {
+            count += word.count_ones();
+        }
+        count
+    }
+}
+
+/// The error returned when creating a [`WideBitBag`] from words which contain bits set which aren't represented by flags
+#[non_exhaustive]
+pub struct WideNonFlagBits<PossibleFlagsT: WideBitBaggable, const N: usize> {
+    unrecognised: [u64; N],
+    _flags: PhantomData<fn() -> PossibleFlagsT>,
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> WideNonFlagBits<PossibleFlagsT, N> {
+    /// The bits which weren't recognised
+    pub fn unrecognised(&self) -> [u64; N] {
+        self.unrecognised
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Debug for WideNonFlagBits<PossibleFlagsT, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WideNonFlagBits")
+            .field("unrecognised", &self.unrecognised)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> core::error::Error
+    for WideNonFlagBits<PossibleFlagsT, N>
+{
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Display
+    for WideNonFlagBits<PossibleFlagsT, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The bits {:#x?} are not accounted for in the enum {}",
+            self.unrecognised,
+            type_name::<PossibleFlagsT>()
+        )
+    }
+}
+
+/// The error returned when decoding a [`WideBitBag`] fails
+#[non_exhaustive]
+pub enum WideDecodeError<PossibleFlagsT: WideBitBaggable, const N: usize> {
+    /// There were fewer than [`WideBitBag::ENCODED_LEN`] bytes
+    BufferTooShort(BufferTooShort),
+    /// The bytes had bits set which aren't defined in the enum
+    NonFlagBits(WideNonFlagBits<PossibleFlagsT, N>),
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Debug for WideDecodeError<PossibleFlagsT, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WideDecodeError::BufferTooShort(e) => f.debug_tuple("BufferTooShort").field(e).finish(),
+            WideDecodeError::NonFlagBits(e) => f.debug_tuple("NonFlagBits").field(e).finish(),
+        }
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> core::error::Error
+    for WideDecodeError<PossibleFlagsT, N>
+{
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Display
+    for WideDecodeError<PossibleFlagsT, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WideDecodeError::BufferTooShort(e) => Display::fmt(e, f),
+            WideDecodeError::NonFlagBits(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Display for WideBitBag<PossibleFlagsT, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("<unset>");
+        }
+
+        let mut first = true;
+
+        for (name, _, bit) in PossibleFlagsT::VARIANTS {
+            let (word, bit) = locate(*bit);
+            if self.words[word] & bit != 0 {
+                match first {
+                    true => {
+                        f.write_str(name)?;
+                        first = false
+                    }
+                    false => write!(f, " | {name}")?,
+                }
+            }
+        }
+
+        if self.has_unrecognised_bits() {
+            match first {
+                true => f.write_str("<unrecognised bits>")?,
+                false => f.write_str(" | <unrecognised bits>")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Debug for WideBitBag<PossibleFlagsT, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WideBitBag")
+            .field("words", &self.words)
+            .finish()
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> PartialEq for WideBitBag<PossibleFlagsT, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Eq for WideBitBag<PossibleFlagsT, N> {}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Hash for WideBitBag<PossibleFlagsT, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.words.hash(state);
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Clone for WideBitBag<PossibleFlagsT, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Copy for WideBitBag<PossibleFlagsT, N> {}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Default for WideBitBag<PossibleFlagsT, N> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> FromIterator<PossibleFlagsT>
+    for WideBitBag<PossibleFlagsT, N>
+{
+    fn from_iter<I: IntoIterator<Item = PossibleFlagsT>>(flags: I) -> Self {
+        let mut bag = Self::empty();
+        bag.extend(flags);
+        bag
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Extend<PossibleFlagsT>
+    for WideBitBag<PossibleFlagsT, N>
+{
+    fn extend<I: IntoIterator<Item = PossibleFlagsT>>(&mut self, flags: I) {
+        for flag in flags {
+            self.set(flag);
+        }
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> Not for WideBitBag<PossibleFlagsT, N> {
+    type Output = Self;
+
+    /// Flip the defined bits, clearing any unrecognised bits
+    fn not(self) -> Self::Output {
+        Self::new_masked(not(self.words))
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitAnd for WideBitBag<PossibleFlagsT, N> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::new(and(self.words, rhs.words))
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitAndAssign
+    for WideBitBag<PossibleFlagsT, N>
+{
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitOr for WideBitBag<PossibleFlagsT, N> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::new(or(self.words, rhs.words))
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitOrAssign
+    for WideBitBag<PossibleFlagsT, N>
+{
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitOr<PossibleFlagsT>
+    for WideBitBag<PossibleFlagsT, N>
+{
+    type Output = Self;
+
+    fn bitor(mut self, rhs: PossibleFlagsT) -> Self::Output {
+        *self.set(rhs)
+    }
+}
+
+impl<PossibleFlagsT: WideBitBaggable, const N: usize> BitOrAssign<PossibleFlagsT>
+    for WideBitBag<PossibleFlagsT, N>
+{
+    fn bitor_assign(&mut self, rhs: PossibleFlagsT) {
+        self.set(rhs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WideBitBag;
+    use crate as bitbag;
+    use crate::WideBitBaggable;
+
+    #[derive(Debug, Clone, Copy, PartialEq, WideBitBaggable)]
+    #[repr(u16)]
+    enum Perm {
+        Low = 0,
+        Mid = 100,
+        High = 255,
+    }
+
+    type Perms = WideBitBag<Perm, 4>;
+
+    #[test]
+    fn flags() {
+        assert_eq!(Perm::BITS, 256);
+        let mut bag = Perms::empty();
+        assert!(bag.is_empty());
+        bag.set(Perm::Low).set(Perm::High);
+        assert!(bag.is_set(Perm::High));
+        assert!(!bag.is_set(Perm::Mid));
+        assert_eq!(bag.get(), [1, 0, 0, 1 << 63]);
+        assert_eq!(bag.iter().collect::<Vec<_>>(), [Perm::Low, Perm::High]);
+        bag.unset(Perm::Low);
+        assert_eq!(bag.to_string(), "High");
+        assert_eq!(Perms::empty().to_string(), "<unset>");
+    }
+
+    #[test]
+    fn unrecognised() {
+        let bag = Perms::new([0b11, 0, 0, 0]);
+        assert!(bag.has_unrecognised_bits());
+        assert_eq!(bag.to_string(), "Low | <unrecognised bits>");
+        assert_eq!(Perms::new_masked(bag.get()).get(), [1, 0, 0, 0]);
+        let e = Perms::new_strict(bag.get()).unwrap_err();
+        assert_eq!(e.unrecognised(), [0b10, 0, 0, 0]);
+        assert_eq!(Perms::all().get(), [1, 1 << 36, 0, 1 << 63]);
+    }
+
+    #[test]
+    fn ops() {
+        let low_mid: Perms = [Perm::Low, Perm::Mid].iter().copied().collect();
+        let high = Perms::empty() | Perm::High;
+        assert_eq!(low_mid | high, Perms::all());
+        assert!((low_mid & high).is_empty());
+        assert_eq!(!low_mid, high);
+    }
+
+    #[test]
+    fn lanes() {
+        // more than one chunk of lanes, plus a remainder
+        let left = [u64::MAX, 0, 0b1010, 1, 2, 3, 4, 5, 6, u64::MAX];
+        let right = [1, 1, 0b0110, 1, 1, 1, 1, 1, 1, 0];
+        assert_eq!(super::and(left, right), [1, 0, 0b0010, 1, 0, 1, 0, 1, 0, 0]);
+        assert_eq!(
+            super::or(left, right),
+            [u64::MAX, 1, 0b1110, 1, 3, 3, 5, 5, 7, u64::MAX]
+        );
+        assert_eq!(super::not(right)[1], !1);
+        assert_eq!(super::not(right)[9], u64::MAX);
+        assert_eq!(
+            super::lanes::count_ones(&left),
+            64 * 2 + 2 + 1 + 1 + 2 + 1 + 2 + 2
+        );
+    }
+
+    #[test]
+    fn counting() {
+        let all = Perms::all();
+        assert_eq!(all.count_ones(), 3);
+        assert!(all.contains(&(Perms::empty() | Perm::Mid)));
+        assert!(!(Perms::empty() | Perm::Mid).contains(&all));
+    }
+
+    #[test]
+    fn bytes() {
+        let bag: Perms = [Perm::Mid, Perm::High].iter().copied().collect();
+        let mut buf = [0; 40];
+        assert_eq!(bag.encode_into(&mut buf).unwrap(), 32);
+        assert_eq!(buf[12], 1 << 4);
+        assert_eq!(buf[31], 0x80);
+        assert_eq!(Perms::decode_from(&buf).unwrap(), bag);
+        assert!(Perms::decode_from(&buf[..31]).is_err());
+        buf[0] = 0b10;
+        assert!(Perms::decode_from(&buf).is_err());
+        assert!(bag.encode_into(&mut buf[..8]).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! A macro wrapping groups of [windows-sys](https://docs.rs/windows-sys) constants into enums, enabled by the `windows-sys` feature.
+//!
+//! windows-sys exposes flags, like access rights, as loose `u32` constants,
+//! so [`windows_sys_flags!`](crate::windows_sys_flags) declares a `#[repr(u32)]` [`BitBaggable`](crate::BitBaggable) enum with them as discriminants.
+//! Each constant is checked at compile time: it must be a `u32`, and it mustn't be zero.
+//! ```
+//! use bitbag::BitOr;
+//! use windows_sys::Win32::Storage::FileSystem::{FILE_APPEND_DATA, FILE_READ_DATA, FILE_WRITE_DATA};
+//!
+//! bitbag::windows_sys_flags! {
+//!     #[derive(Debug, Clone, Copy, BitOr)]
+//!     pub enum FileAccess {
+//!         Read = FILE_READ_DATA,
+//!         Write = FILE_WRITE_DATA,
+//!         Append = FILE_APPEND_DATA,
+//!     }
+//! }
+//!
+//! let access = FileAccess::Read | FileAccess::Append;
+//! assert_eq!(access.get(), FILE_READ_DATA | FILE_APPEND_DATA);
+//! ```
+//! ```compile_fail
+//! const NONE: u32 = 0;
+//!
+//! bitbag::windows_sys_flags! {
+//!     enum FileAccess {
+//!         None = NONE,
+//!     }
+//! }
+//! ```
+
+/// Declare `#[repr(u32)]` [`BitBaggable`](crate::BitBaggable) enums with windows-sys constants as discriminants,
+/// failing to compile if any constant is zero.
+///
+/// Attributes on the enum and its variants are kept, so further derives, like `BitOr`, can be added.
+/// ```ignore
+/// bitbag::windows_sys_flags! {
+///     /// Rights for `OpenProcess`
+///     #[derive(Debug, BitOr)]
+///     pub enum ProcessAccess {
+///         Terminate = PROCESS_TERMINATE,
+///         QueryInformation = PROCESS_QUERY_INFORMATION,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! windows_sys_flags {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:expr),* $(,)?
+        }
+    )*) => {$(
+        $(#[$meta])*
+        #[derive($crate::BitBaggable)]
+        #[repr(u32)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant = $value,)*
+        }
+
+        const _: () = {
+            $(::core::assert!(
+                $value != 0,
+                ::core::concat!(
+                    "`", ::core::stringify!($value), "` is zero, so it can't be a flag of `",
+                    ::core::stringify!($name), "`",
+                ),
+            );)*
+        };
+    )*};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as bitbag;
+    use crate::{BitBag, BitOr};
+    use windows_sys::Win32::{
+        Storage::FileSystem::{FILE_GENERIC_READ, FILE_READ_DATA, FILE_WRITE_DATA},
+        System::Threading::{PROCESS_QUERY_INFORMATION, PROCESS_TERMINATE},
+    };
+
+    crate::windows_sys_flags! {
+        #[derive(Debug, Clone, Copy, PartialEq, BitOr)]
+        enum FileAccess {
+            ReadData = FILE_READ_DATA,
+            WriteData = FILE_WRITE_DATA,
+            /// A composite of several rights
+            GenericRead = FILE_GENERIC_READ,
+        }
+
+        #[derive(Debug, Clone, Copy, BitOr)]
+        pub(crate) enum ProcessAccess {
+            Terminate = PROCESS_TERMINATE,
+            QueryInformation = PROCESS_QUERY_INFORMATION,
+        }
+    }
+
+    #[test]
+    fn access_masks() {
+        let access = FileAccess::ReadData | FileAccess::WriteData;
+        assert_eq!(access.get(), FILE_READ_DATA | FILE_WRITE_DATA);
+        let bag = BitBag::<FileAccess>::new_strict(FILE_GENERIC_READ).unwrap();
+        assert!(bag.is_set(FileAccess::GenericRead));
+        assert!(bag.is_set(FileAccess::ReadData));
+
+        let process = ProcessAccess::Terminate | ProcessAccess::QueryInformation;
+        assert_eq!(process.to_string(), "Terminate | QueryInformation");
+    }
+}